@@ -17,21 +17,40 @@ uniform sampler2D u_texture;
 uniform float u_time;
 uniform vec4 u_mask_color;
 uniform vec4 u_mask2_color;
+uniform vec4 u_treasure_color;
+uniform vec4 u_shop_color;
+uniform vec4 u_challenge_color;
+uniform vec4 u_rest_color;
 uniform mat3 u_world_matrix;
 
 void main() {
     vec4 tex_color = texture2D(u_texture, v_vt);
-    if (tex_color != u_mask_color && tex_color != u_mask2_color) {
-        gl_FragColor = tex_color;
-        return;
-    }
-    
     vec3 world_pos = u_world_matrix * vec3(v_pos, 1.0);
     vec2 pos = world_pos.xy / world_pos.z;
     float size = 2.0;
-    pos = floor(pos / size);
-    float pattern_mask = mod(pos.x + mod(pos.y, 2.0), 2.0);
+
+    float pattern_mask;
+    if (tex_color == u_mask_color || tex_color == u_mask2_color) {
+        // Checkers
+        vec2 cell = floor(pos / size);
+        pattern_mask = mod(cell.x + mod(cell.y, 2.0), 2.0);
+    } else if (tex_color == u_treasure_color) {
+        // Diagonal stripes
+        pattern_mask = mod(floor((pos.x + pos.y) / size), 2.0);
+    } else if (tex_color == u_shop_color) {
+        // Dots
+        vec2 cell = fract(pos / size) - 0.5;
+        pattern_mask = step(length(cell), 0.25);
+    } else if (tex_color == u_challenge_color) {
+        // Scrolling hazard stripes
+        pattern_mask = mod(floor((pos.x - pos.y) / size + u_time), 2.0);
+    } else if (tex_color == u_rest_color) {
+        // Gentle waves
+        pattern_mask = step(0.5, sin(pos.x * 0.5 + sin(pos.y * 0.5 + u_time * 0.5) * 2.0));
+    } else {
+        gl_FragColor = tex_color;
+        return;
+    }
     gl_FragColor = tex_color + pattern_mask * vec4(vec3(0.05), 1.0);
 }
 #endif
-
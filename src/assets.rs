@@ -35,6 +35,7 @@ pub struct Sounds {
     pub expand: geng::Sound,
     pub minigun: geng::Sound,
     pub explosion: geng::Sound,
+    pub squash_warning: geng::Sound,
 }
 
 #[derive(geng::asset::Load)]
@@ -52,6 +53,10 @@ pub struct Sprites {
     #[load(options(filter = "ugli::Filter::Nearest"))]
     pub skull: ugli::Texture,
     #[load(options(filter = "ugli::Filter::Nearest"))]
+    pub ignite: ugli::Texture,
+    #[load(options(filter = "ugli::Filter::Nearest"))]
+    pub venom: ugli::Texture,
+    #[load(options(filter = "ugli::Filter::Nearest"))]
     pub whip: ugli::Texture,
     #[load(options(filter = "ugli::Filter::Nearest"))]
     pub dash: ugli::Texture,
@@ -79,6 +84,10 @@ pub struct Palette {
     pub background: Rgba<f32>,
     pub text: Rgba<f32>,
     pub room: Rgba<f32>,
+    pub room_treasure: Rgba<f32>,
+    pub room_shop: Rgba<f32>,
+    pub room_challenge: Rgba<f32>,
+    pub room_rest: Rgba<f32>,
     pub wall: Rgba<f32>,
     pub wall_block: Rgba<f32>,
     pub player: Rgba<f32>,
@@ -91,8 +100,16 @@ pub struct Palette {
     pub damage: Rgba<f32>,
     pub collision: Rgba<f32>,
     pub upgrade: Rgba<f32>,
+    pub upgrade_rare: Rgba<f32>,
+    pub upgrade_legendary: Rgba<f32>,
     pub pacman_1up: Rgba<f32>,
+    pub snake_food: Rgba<f32>,
     pub idk: Rgba<f32>,
+    pub burn: Rgba<f32>,
+    pub slow: Rgba<f32>,
+    pub stun: Rgba<f32>,
+    pub poison: Rgba<f32>,
+    pub elite: Rgba<f32>,
 }
 
 #[derive(geng::asset::Load, Debug, Clone, Serialize, Deserialize)]
@@ -104,18 +121,283 @@ pub struct Controls {
     pub up: Vec<geng_utils::key::EventKey>,
 
     pub draw: Vec<geng_utils::key::EventKey>,
+
+    /// Start the daily challenge from the starting room.
+    pub daily: Vec<geng_utils::key::EventKey>,
+    /// Stop the game and show the stats of the build.
+    pub pause: Vec<geng_utils::key::EventKey>,
+    pub minimap: Vec<geng_utils::key::EventKey>,
+    /// Toggle the panels of the offered and the collected upgrades.
+    pub choices: Vec<geng_utils::key::EventKey>,
+    pub reroll: Vec<geng_utils::key::EventKey>,
+    /// Banish the upgrade under the cursor, or hold while choosing in the panel.
+    pub banish: Vec<geng_utils::key::EventKey>,
+    /// Open the unlocks in the starting room.
+    pub unlocks: Vec<geng_utils::key::EventKey>,
+    /// Cycle through the unlocked starting weapons.
+    pub starting_weapon: Vec<geng_utils::key::EventKey>,
+    /// Forget all coins and unlocks, pressed twice in the unlocks panel.
+    pub reset_progress: Vec<geng_utils::key::EventKey>,
+    /// Switch to the game mode at the same position in [GameMode::ALL], in the starting room.
+    pub modes: Vec<Vec<geng_utils::key::EventKey>>,
+    /// Toggle the mutator at the same position in the config, in the starting room.
+    pub mutators: Vec<Vec<geng_utils::key::EventKey>>,
 }
 
 #[derive(geng::asset::Load, Debug, Clone, Serialize, Deserialize)]
 #[load(serde = "ron")]
 pub struct Config {
     pub starting_area: vec2<Coord>,
+    /// Shapes of the generated rooms.
+    pub room_shapes: Vec<RoomShapeConfig>,
+    pub room_kinds: RoomKindsConfig,
+    pub routes: RoutesConfig,
+    pub waves: WavesConfig,
+    pub obstacles: ObstaclesConfig,
+    /// Objects that can be spawned in every room.
+    pub objects: Vec<ObjectSpawnConfig>,
+    pub explosions: ExplosionsConfig,
     pub upgrades_per_level: usize,
+    pub upgrade_choices: UpgradeChoicesConfig,
+    /// Chances of the upgrades being offered, by rarity.
+    pub rarities: Vec<RarityConfig>,
+    pub legendaries: LegendaryConfig,
     pub difficulty: DifficultyConfig,
+    pub compression: CompressionConfig,
     pub score: ScoreConfig,
     pub player: PlayerConfig,
     pub enemies: HashMap<String, EnemyConfig>,
+    pub elites: EliteConfig,
     pub bosses: Vec<BossConfig>,
+    pub endless: EndlessConfig,
+    pub modes: ModesConfig,
+    pub daily: DailyConfig,
+    /// Mutators that can be selected before a run.
+    pub mutators: Vec<MutatorConfig>,
+    pub meta: MetaConfig,
+}
+
+impl Config {
+    /// Find the boss guarding the room, along with the endless loop it belongs to.
+    pub fn boss_at(&self, room: usize) -> Option<(&BossConfig, usize)> {
+        let endless_loop = self.endless_loop(room);
+        if endless_loop == 0 {
+            return self
+                .bosses
+                .iter()
+                .find(|boss| boss.room == room)
+                .map(|boss| (boss, 0));
+        }
+
+        // Bosses recur evenly spaced within each loop
+        let last = self.bosses.iter().map(|boss| boss.room).max()?;
+        let cycle = self.endless.cycle.max(self.bosses.len());
+        let step = cycle / self.bosses.len();
+        let in_loop = (room - last - 1) % cycle + 1;
+        if in_loop % step != 0 {
+            return None;
+        }
+        self.bosses
+            .get(in_loop / step - 1)
+            .map(|boss| (boss, endless_loop))
+    }
+
+    pub fn mutator_score_multiplier(&self, mutator: &Mutator) -> R32 {
+        self.mutators
+            .iter()
+            .find(|config| config.mutator == *mutator)
+            .map_or(R32::ONE, |config| config.score_multiplier)
+    }
+
+    /// Endless loop the room belongs to, 0 for the rooms up to the final boss.
+    pub fn endless_loop(&self, room: usize) -> usize {
+        let Some(last) = self.bosses.iter().map(|boss| boss.room).max() else {
+            return 0;
+        };
+        if room <= last {
+            return 0;
+        }
+        let cycle = self.endless.cycle.max(self.bosses.len()).max(1);
+        (room - last - 1) / cycle + 1
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomShapeConfig {
+    pub shape: RoomShape,
+    /// Relative chance of the shape being picked.
+    pub weight: R32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomKindsConfig {
+    /// Kinds of the rooms not guarded by a boss.
+    /// A special room is always followed by a combat room.
+    pub table: Vec<RoomKindConfig>,
+    /// Number of upgrades for sale in a shop.
+    pub shop_upgrades: usize,
+    /// Price of an upgrade before any room is cleared.
+    pub shop_price: Score,
+    /// Extra price for every room cleared.
+    pub shop_price_scaling: Score,
+    /// Time to survive in a challenge room.
+    pub challenge_duration: Time,
+    /// Delay between the waves of a challenge.
+    pub challenge_wave_delay: Time,
+    /// Placed in the middle of a rest room.
+    pub rest_fountain: ObjectConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomKindConfig {
+    pub kind: RoomKind,
+    /// Relative chance of the kind being picked.
+    pub weight: R32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeChoicesConfig {
+    /// Rerolls at the start of a run.
+    pub rerolls: usize,
+    /// Banishes at the start of a run.
+    pub banishes: usize,
+    /// Earned for every boss killed.
+    pub boss_rerolls: usize,
+    pub boss_banishes: usize,
+    /// Earned for finding a treasure room.
+    pub treasure_rerolls: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RarityConfig {
+    pub rarity: Rarity,
+    /// Relative chance of an upgrade of the rarity being offered.
+    pub weight: R32,
+    /// Extra weight for every point of difficulty.
+    pub difficulty_scaling: R32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegendaryConfig {
+    /// Delay before a whip stroke hits again.
+    pub echo_delay: Time,
+    /// Fraction of the whip damage dealt by the echo.
+    pub echo_damage: R32,
+    /// Width multiplier of the echoes with the Reverb synergy.
+    pub reverb_width: R32,
+    pub trail_duration: Time,
+    pub trail_width: Coord,
+    /// Fraction of the dash damage dealt every second.
+    pub trail_damage: R32,
+    /// Duration multiplier of the trail with the Afterburn synergy.
+    pub afterburn_duration: R32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutesConfig {
+    /// Range of the difficulty multiplier of the rooms to fight in.
+    /// The score for clearing a room is multiplied as well.
+    pub min_difficulty: R32,
+    pub max_difficulty: R32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WavesConfig {
+    /// Time the spawn points are marked before the enemies appear.
+    pub telegraph: Time,
+    /// Enemies wait to appear while the player stands this close.
+    pub player_distance: Coord,
+    /// One layout is picked for every room.
+    /// Layouts listing the kind of the room take priority over the generic ones.
+    pub layouts: Vec<WaveLayoutConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveLayoutConfig {
+    /// Relative chance of the layout being picked.
+    pub weight: R32,
+    /// Fraction of the room's difficulty budget spent on each wave.
+    pub waves: Vec<R32>,
+    pub trigger: WaveTrigger,
+    /// Kinds of rooms the layout is made for, any kind if empty.
+    #[serde(default)]
+    pub kinds: Vec<RoomKind>,
+    /// Only used in rooms with at least this much area.
+    #[serde(default)]
+    pub min_area: Option<Coord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObstaclesConfig {
+    /// Maximum number of obstacles in a room.
+    pub max_count: usize,
+    /// Number of obstacles per unit of room area.
+    pub density: R32,
+    pub table: Vec<ObstacleConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObstacleConfig {
+    pub kind: ObstacleKind,
+    /// Relative chance of the obstacle being picked.
+    pub weight: R32,
+    /// Size of the obstacle, randomly rotated by 90 degrees.
+    pub size: vec2<Coord>,
+    /// Whether the obstacle is round instead of rectangular.
+    #[serde(default)]
+    pub round: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplosionsConfig {
+    /// Delay before an explosion sets off the next barrel.
+    pub chain_delay: Time,
+    /// Impulse at the center of an explosion.
+    pub knockback: Coord,
+    /// Fraction of the damage the player takes at the edge of an explosion.
+    pub player_falloff: R32,
+    /// Damage multiplier for the player caught in an exploding object.
+    pub object_player_damage: R32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectSpawnConfig {
+    pub object: ObjectConfig,
+    /// Chance of the object appearing in a room.
+    pub chance: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ObjectConfig {
+    /// Range and damage scale with the whip.
+    ExplosiveBarrel {
+        extra_range: Coord,
+        damage_multiplier: R32,
+    },
+    FreezeBarrel {
+        range: Coord,
+        stun: Time,
+    },
+    Turret {
+        range: Coord,
+        damage: Hp,
+        speed: Coord,
+        cooldown: Time,
+        shots: usize,
+    },
+    BouncePad {
+        strength: Coord,
+    },
+    HealingFountain {
+        heal: Hp,
+        uses: usize,
+        cooldown: Time,
+    },
+    /// Spawned in pairs.
+    Teleporter {
+        cooldown: Time,
+    },
+    Crate,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +432,26 @@ pub struct PlayerConfig {
     pub shield: Shape,
 }
 
+impl PlayerConfig {
+    pub fn weapon(&self, weapon: Weapon) -> &DrawConfig {
+        match weapon {
+            Weapon::Whip => &self.whip,
+            Weapon::Dash => &self.dash,
+            Weapon::Bow => &self.bow,
+            Weapon::FishingRod => &self.fishing,
+        }
+    }
+
+    pub fn weapon_mut(&mut self, weapon: Weapon) -> &mut DrawConfig {
+        match weapon {
+            Weapon::Whip => &mut self.whip,
+            Weapon::Dash => &mut self.dash,
+            Weapon::Bow => &mut self.bow,
+            Weapon::FishingRod => &mut self.fishing,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnemyConfig {
     pub cost: Option<R32>,
@@ -162,6 +464,39 @@ pub struct EnemyConfig {
     pub acceleration: Coord,
     pub shape: Shape,
     pub ai: EnemyAI,
+    /// Effects applied to the player on contact.
+    #[serde(default)]
+    pub effects: Vec<StatusEffectConfig>,
+    /// Boss phases, in the order they are entered.
+    #[serde(default)]
+    pub phases: Vec<BossPhaseConfig>,
+}
+
+impl EnemyConfig {
+    /// Combined damage multiplier of the boss phases entered so far.
+    pub fn phase_damage_multiplier(&self, phase: usize) -> R32 {
+        self.phases
+            .iter()
+            .take(phase)
+            .filter_map(|phase| phase.damage_multiplier)
+            .fold(R32::ONE, |acc, multiplier| acc * multiplier)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BossPhaseConfig {
+    /// The phase starts once the health ratio drops to this value.
+    pub health_threshold: R32,
+    /// Duration of the invulnerability during the transition.
+    #[serde(default)]
+    pub invulnerability: Time,
+    pub speed_multiplier: Option<R32>,
+    pub damage_multiplier: Option<R32>,
+    /// Replaces the behaviour of the boss.
+    pub ai: Option<EnemyAI>,
+    /// Names of the enemies summoned at the start of the phase.
+    #[serde(default)]
+    pub minions: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -170,6 +505,24 @@ pub struct EnemyGrouping {
     pub chance: R32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EliteConfig {
+    /// Difficulty at which elites start to appear.
+    pub min_difficulty: R32,
+    /// Chance for a spawned enemy to become an elite.
+    pub chance: R32,
+    pub max_modifiers: usize,
+    pub modifiers: Vec<EliteModifierConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EliteModifierConfig {
+    /// Difficulty budget spent on the modifier.
+    pub cost: R32,
+    pub score_multiplier: R32,
+    pub modifier: EliteModifier,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DrawConfig {
     pub cooldown: Bounded<Time>,
@@ -178,10 +531,96 @@ pub struct DrawConfig {
     pub width: Coord,
     pub damage: Hp,
     pub invincibility_time: Time,
+    /// Effects applied to the enemies hit.
+    #[serde(default)]
+    pub effects: Vec<StatusEffectConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MutatorConfig {
+    pub mutator: Mutator,
+    pub score_multiplier: R32,
+}
+
+/// Progress kept between runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetaConfig {
+    /// Score needed to earn a coin at the end of a run.
+    pub score_per_coin: Score,
+    /// Unlocks for sale between runs, in the order of the hotkeys.
+    pub unlocks: Vec<UnlockConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnlockConfig {
+    pub unlock: Unlock,
+    pub cost: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyConfig {
+    /// Number of mutators in a daily challenge.
+    pub count: usize,
+    pub mutators: Vec<Mutator>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModesConfig {
+    /// Duration of a time attack run.
+    pub time_attack: Time,
+    /// Time added for every room cleared in time attack.
+    pub time_attack_room_bonus: Time,
+    /// Fraction of max health restored after every boss rush fight.
+    pub boss_rush_heal: R32,
+    /// Number of barrels in every room in pacifist.
+    pub pacifist_barrels: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    pub mode: CompressionMode,
+    /// Shrink speed of a single room.
+    pub speed: Coord,
+    /// Shrink speed scales as `rooms ^ exponent`.
+    pub room_exponent: R32,
+    /// Rooms thinner than that get squashed.
+    pub min_size: Coord,
+    /// Distance to the squash size at which the player gets warned.
+    pub warning_distance: Coord,
+    /// Whether the boss room stays intact while the boss is alive.
+    pub skip_boss_rooms: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum CompressionMode {
+    /// Push the wall opposite to the next room.
+    Linear,
+    /// Same as linear, but only for `shrink` seconds out of every `shrink + pause`.
+    Pulse { shrink: Time, pause: Time },
+    /// Push the walls in the direction of the last unlocked room.
+    LastUnlocked,
+    /// Push all walls towards the center of the room.
+    Center,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndlessConfig {
+    /// Number of rooms in a loop.
+    pub cycle: usize,
+    /// Boss health increase per loop.
+    pub health_scaling: R32,
+    /// Boss damage increase per loop.
+    pub damage_scaling: R32,
+    /// Pool of modifiers rolled at the start of every loop.
+    pub modifiers: Vec<EndlessModifier>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BossConfig {
+    /// Shown on the name card and above the health bar.
+    pub name: String,
+    /// Duration of the intro, during which the bosses stay idle.
+    pub intro: Time,
     pub room: usize,
     pub room_size: vec2<Coord>,
     pub enemies: Vec<String>,
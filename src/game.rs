@@ -1,5 +1,8 @@
 use crate::{
+    history::{RunHistory, RunRecord},
+    leaderboard::{Leaderboard, LeaderboardEntry},
     prelude::*,
+    progress::MetaProgress,
     render::{GameRender, SwapBuffer},
 };
 
@@ -15,6 +18,15 @@ pub struct GameState {
 
     render: GameRender,
     model: Model,
+    leaderboard: Leaderboard,
+    history: RunHistory,
+    progress: MetaProgress,
+    paused: bool,
+    show_minimap: bool,
+    show_choices: bool,
+    show_unlocks: bool,
+    /// The progress reset was pressed once and waits for a confirmation.
+    confirm_reset: bool,
 
     playing_intro: bool,
     music: geng::SoundEffect,
@@ -31,6 +43,9 @@ pub struct CursorState {
 
 impl GameState {
     pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
+        let progress = MetaProgress::load();
+        let mut options = RunOptions::new(GameMode::Normal);
+        progress.apply(&mut options);
         Self {
             geng: geng.clone(),
             assets: assets.clone(),
@@ -45,7 +60,15 @@ impl GameState {
             unit_quad: geng_utils::geometry::unit_quad_geometry(geng.ugli()),
 
             render: GameRender::new(geng, assets),
-            model: Model::new(assets.config.clone()),
+            model: Model::new(assets.config.clone(), options),
+            leaderboard: Leaderboard::load(),
+            history: RunHistory::load(),
+            progress,
+            paused: false,
+            show_minimap: true,
+            show_choices: false,
+            show_unlocks: false,
+            confirm_reset: false,
 
             playing_intro: true,
             music: {
@@ -93,6 +116,16 @@ impl GameState {
         }
     }
 
+    /// Start a new run with the progress applied.
+    fn new_run(&self, mut options: RunOptions) -> Model {
+        self.progress.apply(&mut options);
+        Model::new(self.assets.config.clone(), options)
+    }
+
+    fn in_starting_room(&self) -> bool {
+        self.model.in_starting_room()
+    }
+
     fn play_sfx(&self, sfx: &geng::Sound) {
         let mut sfx = sfx.play();
         sfx.set_volume(self.volume);
@@ -116,8 +149,7 @@ impl geng::State for GameState {
             .as_r32();
         self.model.cursor_pos = self.cursor.world_pos;
 
-        let intro =
-            self.model.rooms.contains(Index::from_raw_parts(0, 0)) && self.model.rooms.len() == 1;
+        let intro = self.in_starting_room();
         if intro && !self.playing_intro {
             self.music.stop();
             self.music = self.assets.music_intro.play();
@@ -134,6 +166,12 @@ impl geng::State for GameState {
     fn fixed_update(&mut self, delta_time: f64) {
         let delta_time = Time::new(delta_time as f32);
 
+        if self.paused {
+            self.drawing_sfx.set_volume(0.0);
+            self.helicopter_sfx.set_volume(0.0);
+            return;
+        }
+
         let input = self.get_controls();
         self.model.update(input, delta_time);
 
@@ -153,7 +191,37 @@ impl geng::State for GameState {
                     SoundEvent::Expand => self.play_sfx(&self.assets.sounds.expand),
                     SoundEvent::Minigun => self.play_sfx(&self.assets.sounds.minigun),
                     SoundEvent::Explosion => self.play_sfx(&self.assets.sounds.explosion),
+                    SoundEvent::SquashWarning => self.play_sfx(&self.assets.sounds.squash_warning),
                 },
+                Event::RunStarted => {
+                    if let Some(daily) = self.model.options.daily {
+                        if !self.history.start_daily(daily) {
+                            log::info!(
+                                "Daily challenge already attempted, the run is not recorded"
+                            );
+                        }
+                        self.history.save();
+                    }
+                }
+                Event::GameOver => {
+                    if self.model.options.daily.is_none() {
+                        let entry = LeaderboardEntry::from_model(&self.model);
+                        if let Some(place) = self.leaderboard.submit(entry) {
+                            log::info!("New leaderboard entry at place {}", place + 1);
+                        }
+                        self.leaderboard.save();
+                    }
+                    if !self.history.record(RunRecord::from_model(&self.model)) {
+                        log::info!("Daily challenge already attempted, the run is not recorded");
+                    }
+                    self.history.save();
+
+                    let coins = self
+                        .progress
+                        .earn(self.model.score, &self.assets.config.meta);
+                    log::info!("Earned {} coins", coins);
+                    self.progress.save();
+                }
             }
         }
         if kill {
@@ -168,11 +236,172 @@ impl geng::State for GameState {
     }
 
     fn handle_event(&mut self, event: geng::Event) {
+        if geng_utils::key::is_event_press(&event, &self.assets.controls.pause) {
+            self.paused = !self.paused;
+            return;
+        }
+        if geng_utils::key::is_event_press(&event, &self.assets.controls.minimap) {
+            self.show_minimap = !self.show_minimap;
+            return;
+        }
+        if geng_utils::key::is_event_press(&event, &self.assets.controls.choices) {
+            self.show_choices = !self.show_choices;
+            return;
+        }
+
+        // Upgrade choice
+        if !self.paused && !self.model.upgrade_choices().is_empty() {
+            if geng_utils::key::is_event_press(&event, &self.assets.controls.reroll) {
+                self.model.reroll_upgrades();
+                return;
+            }
+            let banish =
+                geng_utils::key::is_key_pressed(self.geng.window(), &self.assets.controls.banish);
+            if geng_utils::key::is_event_press(&event, &self.assets.controls.banish) {
+                if let Some(index) = self.model.hovered_upgrade() {
+                    self.model.banish_upgrade(index);
+                }
+                return;
+            }
+            if let geng::Event::KeyPress { key } = event {
+                let choice = [
+                    geng::Key::Digit1,
+                    geng::Key::Digit2,
+                    geng::Key::Digit3,
+                    geng::Key::Digit4,
+                    geng::Key::Digit5,
+                ]
+                .iter()
+                .position(|&other| other == key);
+                if let Some(choice) = choice.filter(|_| self.show_choices) {
+                    if banish {
+                        if let Some(&index) = self.model.upgrade_choices().get(choice) {
+                            self.model.banish_upgrade(index);
+                        }
+                    } else {
+                        self.model.choose_upgrade(choice);
+                    }
+                    return;
+                }
+            }
+        }
+
+        // Unlocks
+        if self.in_starting_room() && !self.paused {
+            let controls = &self.assets.controls;
+            if geng_utils::key::is_event_press(&event, &controls.unlocks) {
+                self.show_unlocks = !self.show_unlocks;
+                self.confirm_reset = false;
+                return;
+            }
+            if geng_utils::key::is_event_press(&event, &controls.starting_weapon) {
+                self.progress.next_weapon();
+                self.progress.save();
+                self.model = self.new_run(self.model.options.clone());
+                return;
+            }
+            if self.show_unlocks {
+                if geng_utils::key::is_event_press(&event, &controls.reset_progress) {
+                    // Wipes the coins, so it has to be pressed twice
+                    if !self.confirm_reset {
+                        self.confirm_reset = true;
+                        return;
+                    }
+                    self.confirm_reset = false;
+                    self.progress.reset();
+                    self.progress.save();
+                    log::info!("Progress reset");
+                    self.model = self.new_run(self.model.options.clone());
+                    return;
+                }
+                if let geng::Event::KeyPress { key } = event {
+                    self.confirm_reset = false;
+                    let index = [
+                        geng::Key::Digit1,
+                        geng::Key::Digit2,
+                        geng::Key::Digit3,
+                        geng::Key::Digit4,
+                        geng::Key::Digit5,
+                        geng::Key::Digit6,
+                        geng::Key::Digit7,
+                        geng::Key::Digit8,
+                        geng::Key::Digit9,
+                    ]
+                    .iter()
+                    .position(|&other| other == key);
+                    if let Some(index) = index {
+                        if self.progress.buy(index, &self.assets.config.meta) {
+                            self.progress.save();
+                            self.model = self.new_run(self.model.options.clone());
+                        }
+                        return;
+                    }
+                }
+            }
+        }
+
         match event {
             geng::Event::KeyPress { key: geng::Key::R }
                 if self.geng.window().is_key_pressed(geng::Key::ControlLeft) =>
             {
                 self.model.reset();
+                self.paused = false;
+            }
+            geng::Event::KeyPress { .. } if self.in_starting_room() && !self.paused => {
+                // Mode selection
+                let config = &self.assets.config;
+                let controls = &self.assets.controls;
+                let pressed = |keys: &Vec<geng_utils::key::EventKey>| {
+                    geng_utils::key::is_event_press(&event, keys)
+                };
+                let mode = controls
+                    .modes
+                    .iter()
+                    .position(pressed)
+                    .and_then(|i| GameMode::ALL.get(i).copied());
+                let mutator = controls
+                    .mutators
+                    .iter()
+                    .position(pressed)
+                    .and_then(|i| config.mutators.get(i));
+                let options = if pressed(&controls.daily) {
+                    RunOptions::daily(DailyChallenge::today(), config)
+                } else if let Some(mode) = mode {
+                    // Keep the picked mutators, the daily ones are not picked
+                    let mutators = match self.model.options.daily {
+                        Some(_) => Vec::new(),
+                        None => self.model.options.mutators.clone(),
+                    };
+                    RunOptions {
+                        mutators,
+                        ..RunOptions::new(mode)
+                    }
+                } else if let Some(mutator) = mutator {
+                    // Toggle a mutator
+                    if self.model.options.daily.is_some() {
+                        // Daily mutators are fixed
+                        return;
+                    }
+                    let mut options = self.model.options.clone();
+                    if let Some(i) = options
+                        .mutators
+                        .iter()
+                        .position(|other| *other == mutator.mutator)
+                    {
+                        options.mutators.remove(i);
+                    } else {
+                        options.mutators.push(mutator.mutator.clone());
+                    }
+                    self.model = self.new_run(options);
+                    return;
+                } else {
+                    return;
+                };
+                if options.mode != self.model.options.mode
+                    || options.daily != self.model.options.daily
+                {
+                    self.model = self.new_run(options);
+                }
             }
             geng::Event::CursorMove { position } => {
                 self.cursor.screen_pos = position;
@@ -233,6 +462,10 @@ impl geng::State for GameState {
                 u_time: self.model.real_time.as_f32(),
                 u_mask_color: self.assets.palette.room,
                 u_mask2_color: self.assets.palette.background,
+                u_treasure_color: self.assets.palette.room_treasure,
+                u_shop_color: self.assets.palette.room_shop,
+                u_challenge_color: self.assets.palette.room_challenge,
+                u_rest_color: self.assets.palette.room_rest,
                 u_world_matrix: world_matrix,
             },
             ugli::DrawParameters {
@@ -248,7 +481,25 @@ impl geng::State for GameState {
             .draw(&geng::PixelPerfectCamera, &self.geng, post_buffer);
 
         // UI
-        self.render.draw_ui(&self.model, post_buffer);
+        self.render.draw_ui(
+            &self.model,
+            &self.leaderboard,
+            &self.history,
+            self.show_minimap,
+            self.show_choices,
+            post_buffer,
+        );
+        if self.show_unlocks && self.in_starting_room() {
+            self.render.draw_unlocks(
+                &self.progress,
+                &self.assets.config.meta,
+                self.confirm_reset,
+                post_buffer,
+            );
+        }
+        if self.paused {
+            self.render.draw_pause(&self.model, post_buffer);
+        }
 
         // Postprocessing - Hurt
         self.post_buffer.swap();
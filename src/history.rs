@@ -0,0 +1,109 @@
+use crate::prelude::*;
+
+const STORAGE_KEY: &str = "run_history";
+/// Number of runs kept in the history.
+const MAX_RUNS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub mode: GameMode,
+    pub daily: Option<DailyChallenge>,
+    pub mutators: Vec<Mutator>,
+    pub score: Score,
+    pub rooms_cleared: usize,
+    pub bosses_killed: usize,
+}
+
+impl RunRecord {
+    pub fn from_model(model: &Model) -> Self {
+        Self {
+            mode: model.options.mode,
+            daily: model.options.daily,
+            mutators: model.options.mutators.clone(),
+            score: model.score,
+            rooms_cleared: model.rooms_cleared,
+            bosses_killed: model.bosses_killed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunHistory {
+    /// Latest runs, the oldest ones are dropped.
+    pub runs: Vec<RunRecord>,
+    /// Recorded score of every daily challenge played, by day.
+    /// Kept separately so that old runs dropping out do not allow another attempt.
+    pub daily_scores: HashMap<u64, Score>,
+    /// Day of the latest daily challenge played.
+    pub last_daily: Option<u64>,
+    /// Day of the daily challenge started but not finished yet.
+    /// Its attempt is already used up, restarting the run does not give another one.
+    pub daily_in_progress: Option<u64>,
+    /// Number of consecutive days the daily challenge was played.
+    pub daily_streak: usize,
+}
+
+impl RunHistory {
+    pub fn load() -> Self {
+        let mut history: Self = preferences::load(STORAGE_KEY).unwrap_or_default();
+        // Histories saved before the daily scores were kept separately
+        for run in &history.runs {
+            if let Some(daily) = run.daily {
+                history.daily_scores.entry(daily.day).or_insert(run.score);
+                history.last_daily = history.last_daily.max(Some(daily.day));
+            }
+        }
+        history
+    }
+
+    pub fn save(&self) {
+        preferences::save(STORAGE_KEY, self);
+    }
+
+    /// Recorded score of the daily challenge.
+    pub fn daily_result(&self, challenge: DailyChallenge) -> Option<Score> {
+        self.daily_scores.get(&challenge.day).copied()
+    }
+
+    /// Use up the attempt at the daily challenge as soon as the run starts,
+    /// with a score of zero until it is finished.
+    /// Returns whether this run is the attempt.
+    pub fn start_daily(&mut self, daily: DailyChallenge) -> bool {
+        // An abandoned attempt keeps its score
+        self.daily_in_progress = None;
+        if self.daily_result(daily).is_some() {
+            return false;
+        }
+        self.daily_streak = if self.last_daily.map_or(false, |last| last + 1 == daily.day) {
+            self.daily_streak + 1
+        } else {
+            1
+        };
+        self.daily_scores.insert(daily.day, 0);
+        self.last_daily = Some(
+            self.last_daily
+                .map_or(daily.day, |last| last.max(daily.day)),
+        );
+        self.daily_in_progress = Some(daily.day);
+        true
+    }
+
+    /// Record a finished run, only the first attempt at a daily challenge is kept.
+    /// Returns whether the run was recorded.
+    pub fn record(&mut self, record: RunRecord) -> bool {
+        if let Some(daily) = record.daily {
+            if self.daily_in_progress != Some(daily.day) && !self.start_daily(daily) {
+                return false;
+            }
+            self.daily_in_progress = None;
+            self.daily_scores.insert(daily.day, record.score);
+        }
+
+        self.runs.push(record);
+        if self.runs.len() > MAX_RUNS {
+            self.runs.remove(0);
+        }
+        true
+    }
+}
@@ -0,0 +1,93 @@
+use crate::prelude::*;
+
+const STORAGE_KEY: &str = "leaderboard";
+/// Number of entries kept for each category.
+const MAX_ENTRIES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LeaderboardCategory {
+    Normal,
+    /// Runs that made it past the final boss.
+    Endless,
+    TimeAttack,
+    BossRush,
+    Pacifist,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub category: LeaderboardCategory,
+    pub score: Score,
+    pub rooms_cleared: usize,
+    pub endless_loop: usize,
+}
+
+impl LeaderboardEntry {
+    pub fn from_model(model: &Model) -> Self {
+        let endless_loop = model.endless_loop();
+        Self {
+            category: match model.options.mode {
+                GameMode::Normal if endless_loop > 0 => LeaderboardCategory::Endless,
+                GameMode::Normal => LeaderboardCategory::Normal,
+                GameMode::TimeAttack => LeaderboardCategory::TimeAttack,
+                GameMode::BossRush => LeaderboardCategory::BossRush,
+                GameMode::Pacifist => LeaderboardCategory::Pacifist,
+            },
+            score: model.score,
+            rooms_cleared: model.rooms_cleared,
+            endless_loop,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    pub fn load() -> Self {
+        preferences::load(STORAGE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        preferences::save(STORAGE_KEY, self);
+    }
+
+    /// Record the entry and return its place in the category, if it made the cut.
+    pub fn submit(&mut self, entry: LeaderboardEntry) -> Option<usize> {
+        let category = entry.category;
+        // Stable sort keeps the new entry below the older ones with the same score
+        let place = self
+            .top(category)
+            .filter(|other| other.score >= entry.score)
+            .count();
+        self.entries.push(entry);
+        self.entries
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+
+        let mut kept = 0;
+        self.entries.retain(|entry| {
+            if entry.category != category {
+                return true;
+            }
+            kept += 1;
+            kept <= MAX_ENTRIES
+        });
+
+        (place < MAX_ENTRIES).then_some(place)
+    }
+
+    pub fn top(
+        &self,
+        category: LeaderboardCategory,
+    ) -> impl Iterator<Item = &LeaderboardEntry> + '_ {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.category == category)
+    }
+
+    pub fn best(&self, category: LeaderboardCategory) -> Option<Score> {
+        self.top(category).map(|entry| entry.score).next()
+    }
+}
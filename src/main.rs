@@ -1,7 +1,10 @@
 mod assets;
 mod game;
+mod history;
+mod leaderboard;
 mod model;
 mod prelude;
+mod progress;
 mod render;
 mod util;
 
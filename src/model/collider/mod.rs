@@ -55,6 +55,28 @@ impl Collider {
         (self.get_iso(), self.shape.to_parry())
     }
 
+    /// Cast a ray from `origin` in the normalized `direction`.
+    /// Returns the distance to the first intersection within `max_distance`.
+    pub fn raycast(
+        &self,
+        origin: Position,
+        direction: vec2<Coord>,
+        max_distance: Coord,
+    ) -> Option<Coord> {
+        use parry2d::query::RayCast;
+
+        let (iso, shape) = self.to_parry();
+        let origin = origin.as_f32();
+        let direction = direction.as_f32();
+        let ray = parry2d::query::Ray::new(
+            parry2d::math::Point::new(origin.x, origin.y),
+            parry2d::na::Vector2::new(direction.x, direction.y),
+        );
+        shape
+            .cast_ray(&iso, &ray, max_distance.as_f32(), true)
+            .map(r32)
+    }
+
     /// Check whether two colliders are intersecting.
     pub fn check(&self, other: &Self) -> bool {
         let delta = (other.position - self.position).as_f32();
@@ -29,6 +29,21 @@ impl Shape {
         }
     }
 
+    pub fn scaled(self, factor: Coord) -> Self {
+        match self {
+            Shape::Circle { radius } => Shape::Circle {
+                radius: radius * factor,
+            },
+            Shape::Rectangle { width, height } => Shape::Rectangle {
+                width: width * factor,
+                height: height * factor,
+            },
+            Shape::Triangle { height } => Shape::Triangle {
+                height: height * factor,
+            },
+        }
+    }
+
     pub fn to_parry(self) -> Box<dyn parry2d::shape::Shape> {
         match self {
             Shape::Circle { radius } => Box::new(parry2d::shape::Ball::new(radius.as_f32())),
@@ -0,0 +1,59 @@
+use super::*;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A run shared by everyone playing on the same day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyChallenge {
+    /// Days since the unix epoch (UTC).
+    pub day: u64,
+}
+
+impl DailyChallenge {
+    pub fn today() -> Self {
+        let now = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .unwrap_or_default();
+        Self {
+            day: now.as_secs() / SECONDS_PER_DAY,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        // NOTE: splitmix64 so that neighbouring days get unrelated seeds
+        let mut z = self.day.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn mutators(&self, config: &Config) -> Vec<Mutator> {
+        let mut rng = StdRng::seed_from_u64(self.seed());
+        config
+            .daily
+            .mutators
+            .choose_multiple(&mut rng, config.daily.count)
+            .cloned()
+            .collect()
+    }
+
+    /// Calendar date as (year, month, day).
+    pub fn date(&self) -> (i64, u32, u32) {
+        // Days to civil date, from http://howardhinnant.github.io/date_algorithms.html
+        let z = self.day as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
+
+    pub fn name(&self) -> String {
+        let (year, month, day) = self.date();
+        format!("Daily {year}-{month:02}-{day:02}")
+    }
+}
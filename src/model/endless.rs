@@ -0,0 +1,36 @@
+use super::*;
+
+/// Modifier added at the start of every endless loop.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EndlessModifier {
+    EnemyHealth(R32),
+    EnemyDamage(R32),
+    EnemySpeed(R32),
+    /// Added to the chance of spawning an elite enemy.
+    EliteChance(R32),
+}
+
+impl EndlessModifier {
+    pub fn apply(&self, config: &mut EnemyConfig) {
+        match *self {
+            EndlessModifier::EnemyHealth(multiplier) => config.health *= multiplier,
+            EndlessModifier::EnemyDamage(multiplier) => config.damage *= multiplier,
+            EndlessModifier::EnemySpeed(multiplier) => {
+                config.speed *= multiplier;
+                config.acceleration *= multiplier;
+            }
+            EndlessModifier::EliteChance(_) => {}
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match *self {
+            EndlessModifier::EnemyHealth(multiplier) => format!("Health x{:.1}", multiplier),
+            EndlessModifier::EnemyDamage(multiplier) => format!("Damage x{:.1}", multiplier),
+            EndlessModifier::EnemySpeed(multiplier) => format!("Speed x{:.1}", multiplier),
+            EndlessModifier::EliteChance(chance) => {
+                format!("Elites +{:.0}%", chance.as_f32() * 100.0)
+            }
+        }
+    }
+}
@@ -6,9 +6,20 @@ pub struct Enemy {
     pub is_boss: bool,
     pub health: Health,
     pub last_hit: Time,
+    /// Last time the enemy was pushed away, so it can be knocked into pits.
+    pub last_knockback: Time,
     pub invincibility: Bounded<Time>,
+    /// Time left of a longer invulnerability, like a boss phase transition.
+    /// Keeps `invincibility` from running out without raising its max.
+    pub invulnerable: Time,
     pub body: PhysicsBody,
     pub attached_to: Option<(Id, vec2<Coord>)>,
+    pub status: StatusEffects,
+    pub elite: Vec<EliteModifier>,
+    /// Number of boss phases the enemy has gone through.
+    pub phase: usize,
+    /// Severed snake segment, the snake does not eat it back.
+    pub severed: bool,
     pub stats: EnemyConfig,
     pub ai: EnemyAI,
 }
@@ -22,13 +33,84 @@ impl Enemy {
             is_boss: false,
             health: Bounded::new_max(config.health),
             last_hit: r32(-999.0),
+            last_knockback: r32(-999.0),
             invincibility: Bounded::new_zero(r32(0.5)),
+            invulnerable: Time::ZERO,
             body,
             attached_to: None,
+            status: StatusEffects::new(),
+            elite: Vec::new(),
+            phase: 0,
+            severed: false,
             ai: config.ai.clone(),
             stats: config,
         }
     }
+
+    pub fn is_elite(&self) -> bool {
+        !self.elite.is_empty()
+    }
+
+    pub fn make_elite(&mut self, modifiers: Vec<EliteModifierConfig>) {
+        for config in modifiers {
+            self.stats.score = self
+                .stats
+                .score
+                .map(|score| (score as f32 * config.score_multiplier.as_f32()) as Score);
+            if let EliteModifier::Fast { speed_multiplier } = config.modifier {
+                self.stats.speed *= speed_multiplier;
+                self.stats.acceleration *= speed_multiplier;
+            }
+            self.elite.push(config.modifier);
+        }
+    }
+
+    /// Deal damage to the enemy, taking the armor into account.
+    /// Invincible enemies take no damage from any source.
+    pub fn take_damage(&mut self, damage: Hp) {
+        if self.invincibility.is_above_min() {
+            return;
+        }
+        let reduction = self
+            .elite
+            .iter()
+            .map(|modifier| match modifier {
+                EliteModifier::Armored { damage_reduction } => *damage_reduction,
+                _ => R32::ZERO,
+            })
+            .fold(R32::ZERO, R32::add)
+            .min(r32(0.9));
+        self.health.change(-damage * (R32::ONE - reduction));
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EliteModifier {
+    /// Reduces the damage taken by the given fraction.
+    Armored {
+        damage_reduction: R32,
+    },
+    Fast {
+        speed_multiplier: R32,
+    },
+    /// Splits into smaller copies on death.
+    Splitting {
+        count: usize,
+    },
+    /// Explodes on death, hurting the player.
+    Explosive {
+        range: Coord,
+        damage: Hp,
+    },
+    /// Regenerates a fraction of max health per second.
+    Regenerating {
+        rate: R32,
+    },
+    /// Periodically shields the nearby enemies.
+    ShieldedAura {
+        range: Coord,
+        cooldown: Bounded<Time>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,9 +140,22 @@ pub enum EnemyAI {
         #[serde(default)]
         helicopter: HelicopterAI,
     },
+    /// Head of the snake.
+    Snake {
+        #[serde(default)]
+        snake: SnakeAI,
+    },
+    /// Invulnerable body part following the segment it is attached to.
+    SnakeSegment,
+    /// Paints strokes that hurt the player.
+    Painter {
+        #[serde(default)]
+        painter: PainterAI,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PacmanAI {
     pub state: PacmanState,
     pub speed_power: Coord,
@@ -95,10 +190,123 @@ pub struct Pacman1Up {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnakeAI {
+    /// Body segments ordered from the head to the tail.
+    pub segments: Vec<Id>,
+    /// Number of segments left to grow.
+    pub grow: usize,
+    pub segment: Box<EnemyConfig>,
+    /// Distance between the neighbouring segments.
+    pub spacing: Coord,
+    /// Turn speed in degrees per second.
+    pub turn_speed: R32,
+    pub spawn_food: Bounded<Time>,
+}
+
+impl Default for SnakeAI {
+    fn default() -> Self {
+        Self {
+            segments: Vec::new(),
+            grow: 8,
+            segment: Box::new(EnemyConfig {
+                cost: None,
+                score: Some(50),
+                grouping: None,
+                mass: None,
+                health: r32(20.0),
+                damage: r32(10.0),
+                speed: r32(4.0),
+                acceleration: r32(20.0),
+                shape: Shape::circle(0.6),
+                ai: EnemyAI::SnakeSegment,
+                effects: Vec::new(),
+                phases: Vec::new(),
+            }),
+            spacing: r32(1.1),
+            turn_speed: r32(120.0),
+            spawn_food: Bounded::new_max(r32(3.0)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SnakeFood {
+    pub collider: Collider,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PainterAI {
+    pub preferred_distance: Coord,
+    pub cooldown: Bounded<Time>,
+    /// Number of strokes painted at once.
+    pub strokes: usize,
+    pub patterns: Vec<PainterPattern>,
+    pub stroke_width: Coord,
+    pub stroke_damage: Hp,
+    /// Delay before the stroke becomes dangerous.
+    pub telegraph: Time,
+    pub duration: Time,
+    /// Chance for a stroke to block the player from drawing across it.
+    pub blocking_chance: R32,
+}
+
+impl Default for PainterAI {
+    fn default() -> Self {
+        Self {
+            preferred_distance: r32(10.0),
+            cooldown: Bounded::new_max(r32(3.0)),
+            strokes: 1,
+            patterns: vec![PainterPattern::Scribble, PainterPattern::Sweep],
+            stroke_width: r32(0.4),
+            stroke_damage: r32(15.0),
+            telegraph: r32(1.0),
+            duration: r32(2.0),
+            blocking_chance: r32(0.2),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum PainterPattern {
+    /// Random curve ending near the player.
+    Scribble,
+    /// Straight line across the room through the player.
+    Sweep,
+    /// Closed loop around the player.
+    Cage,
+}
+
+#[derive(Debug, Clone)]
+pub struct PainterStroke {
+    pub points: Vec<Position>,
+    pub width: Coord,
+    pub damage: Hp,
+    pub blocking: bool,
+    pub telegraph: Bounded<Time>,
+    pub lifetime: Bounded<Time>,
+}
+
+impl PainterStroke {
+    pub fn is_active(&self) -> bool {
+        self.telegraph.is_min()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct HelicopterAI {
     pub oscilate: Bounded<Time>,
     pub state: HelicopterState,
     pub minigun_bullet: Box<EnemyConfig>,
+    pub minigun_delay: Time,
+    pub minigun_duration: Time,
+    /// Chance to summon minions instead of shooting.
+    pub minion_chance: R32,
+    /// Names of the summoned enemies, spawned from last to first.
+    pub minions: Vec<String>,
+    pub minion_health: R32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -131,7 +339,19 @@ impl Default for HelicopterAI {
                 acceleration: r32(100.0),
                 shape: Shape::circle(0.2),
                 ai: EnemyAI::Bullet,
+                effects: Vec::new(),
+                phases: Vec::new(),
             }),
+            minigun_delay: r32(0.2),
+            minigun_duration: r32(5.0),
+            minion_chance: r32(0.3),
+            minions: [
+                "tank", "shooter", "circle", "shielder", "circle", "circle", "shooter",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            minion_health: r32(1.5),
         }
     }
 }
@@ -0,0 +1,33 @@
+use super::*;
+
+/// Explosion waiting in the queue to be processed.
+#[derive(Debug, Clone)]
+pub struct Explosion {
+    pub position: Position,
+    pub range: Coord,
+    /// Damage at the center, the player takes less towards the edge.
+    pub damage: Hp,
+    /// Effects applied to the enemies caught in the explosion.
+    pub effects: Vec<StatusEffectConfig>,
+    pub source: ExplosionSource,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplosionSource {
+    /// Destroyed object, hurts everyone.
+    Object,
+    /// Player's shot, only hurts the enemies.
+    Bullet,
+    /// Enemy death, only hurts the player.
+    Hostile,
+}
+
+impl ExplosionSource {
+    pub fn hurts_enemies(&self) -> bool {
+        !matches!(self, ExplosionSource::Hostile)
+    }
+
+    pub fn hurts_player(&self) -> bool {
+        !matches!(self, ExplosionSource::Bullet)
+    }
+}
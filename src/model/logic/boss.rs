@@ -0,0 +1,327 @@
+use super::*;
+
+impl Model {
+    /// Move the boss to the next phase once its health drops low enough.
+    pub fn boss_phases(&mut self, enemy: &mut Enemy) {
+        let Some(phase) = enemy.stats.phases.get(enemy.phase) else {
+            return;
+        };
+        if enemy.health.get_ratio() > phase.health_threshold {
+            return;
+        }
+
+        let phase = phase.clone();
+        enemy.phase += 1;
+        log::debug!("Boss entering phase {}", enemy.phase);
+
+        if phase.invulnerability > Time::ZERO {
+            enemy.invulnerable = enemy.invulnerable.max(phase.invulnerability);
+            enemy.invincibility.set_ratio(Time::ONE);
+        }
+        if let Some(multiplier) = phase.speed_multiplier {
+            enemy.stats.speed *= multiplier;
+            enemy.stats.acceleration *= multiplier;
+        }
+        if let Some(multiplier) = phase.damage_multiplier {
+            enemy.stats.damage *= multiplier;
+        }
+        if let Some(ai) = phase.ai {
+            enemy.ai = ai;
+        }
+
+        let mut rng = thread_rng();
+        for name in &phase.minions {
+            let Some(config) = self.config.enemies.get(name) else {
+                log::error!("Enemy named {name:?} not found");
+                continue;
+            };
+            let position = rng.gen_circle(enemy.body.collider.position, r32(3.0));
+            self.spawn_queue.push(Enemy::new(
+                self.id_gen.gen(),
+                EnemyConfig {
+                    health: config.health
+                        + self.config.difficulty.enemy_health_scaling * self.difficulty,
+                    ..config.clone()
+                },
+                position,
+            ));
+        }
+
+        let radius = enemy.body.collider.compute_aabb().size().len() / r32(2.0);
+        self.particles_queue.push(SpawnParticles {
+            kind: ParticleKind::Damage,
+            density: r32(10.0),
+            distribution: ParticleDistribution::Circle {
+                center: enemy.body.collider.position,
+                radius,
+            },
+            velocity: vec2(0.0, 1.0).as_r32(),
+            ..default()
+        });
+        self.events.push(Event::Sound(SoundEvent::Explosion));
+    }
+
+    pub fn snake_head(
+        &mut self,
+        id: Id,
+        body: &mut PhysicsBody,
+        stats: &EnemyConfig,
+        snake: &mut SnakeAI,
+        delta_time: Time,
+    ) {
+        let mut rng = thread_rng();
+        let position = body.collider.position;
+
+        // Steer towards the player
+        let target = self.navigate(position, self.player.body.collider.position);
+        let angle = body.velocity.arg();
+        let turn = ((target - position).arg() - angle)
+            .normalized_pi()
+            .as_radians();
+        let max_turn = Angle::from_degrees(snake.turn_speed * delta_time).as_radians();
+        let angle = angle + Angle::from_radians(turn.clamp(-max_turn, max_turn));
+        body.velocity = angle.unit_vec() * stats.speed;
+        body.angular_velocity = Angle::ZERO;
+        body.collider.rotation = angle;
+
+        // Forget the severed segments
+        if let Some(cut) = snake.segments.iter().position(|id| {
+            !self
+                .enemies
+                .get(id)
+                .map_or(false, |segment| matches!(segment.ai, EnemyAI::SnakeSegment))
+        }) {
+            snake.segments.truncate(cut);
+        }
+
+        // Grow
+        if snake.grow > 0 {
+            snake.grow -= 1;
+            let (tail_id, tail) = snake
+                .segments
+                .last()
+                .and_then(|id| self.enemies.get(id))
+                .map_or((id, position), |tail| {
+                    (tail.id, tail.body.collider.position)
+                });
+            let offset = -angle.unit_vec() * snake.spacing;
+            let mut segment = Enemy::new(
+                self.id_gen.gen(),
+                EnemyConfig {
+                    health: snake.segment.health
+                        + self.config.difficulty.enemy_health_scaling * self.difficulty,
+                    ..(*snake.segment).clone()
+                },
+                tail + offset,
+            );
+            segment.attached_to = Some((tail_id, offset));
+            snake.segments.push(segment.id);
+            self.spawn_queue.push(segment);
+        }
+
+        // Spawn food
+        snake.spawn_food.change(-delta_time);
+        if snake.spawn_food.is_min() {
+            snake.spawn_food.set_ratio(Time::ONE);
+            if let Some((_, room)) = self.rooms.iter().find(|(_, room)| room.contains(position)) {
+                let area = room.area.extend_uniform(r32(-3.0));
+                let position = vec2(
+                    rng.gen_range(area.min.x..=area.max.x),
+                    rng.gen_range(area.min.y..=area.max.y),
+                );
+                self.snake_food.push(SnakeFood {
+                    collider: Collider::new(position, Shape::circle(0.4)),
+                });
+            }
+        }
+
+        // Eat food and crawlers
+        let head = &body.collider;
+        let mut eaten = 0;
+        self.snake_food.retain(|food| {
+            let eat = food.collider.check(head);
+            if eat {
+                eaten += 1;
+            }
+            !eat
+        });
+        self.enemies.retain(|enemy| {
+            let eat = !enemy.is_boss
+                && !enemy.severed
+                && matches!(enemy.ai, EnemyAI::Crawler)
+                && enemy.body.collider.check(head);
+            if eat {
+                eaten += 1;
+            }
+            !eat
+        });
+        if eaten > 0 {
+            snake.grow += eaten;
+            self.particles_queue.push(SpawnParticles {
+                kind: ParticleKind::Heal,
+                distribution: ParticleDistribution::Circle {
+                    center: position,
+                    radius: r32(1.0),
+                },
+                ..default()
+            });
+            self.events.push(Event::Sound(SoundEvent::Kill));
+        }
+    }
+
+    /// Follow the segment ahead, turning into a crawler once severed from the head.
+    pub fn snake_segment(&mut self, enemy: &mut Enemy) {
+        let ahead = enemy.attached_to.and_then(|(id, offset)| {
+            self.enemies
+                .get(&id)
+                .filter(|ahead| matches!(ahead.ai, EnemyAI::Snake { .. } | EnemyAI::SnakeSegment))
+                .map(|ahead| (ahead, offset.len()))
+        });
+        let Some((ahead, spacing)) = ahead else {
+            enemy.ai = EnemyAI::Crawler;
+            enemy.severed = true;
+            enemy.attached_to = None;
+            enemy.invincibility.set_ratio(Time::ZERO);
+            return;
+        };
+
+        // NOTE: segments only take damage by being cut
+        enemy.invincibility.set_ratio(Time::ONE);
+
+        let delta = enemy.body.collider.position - ahead.body.collider.position;
+        enemy.body.collider.position =
+            ahead.body.collider.position + delta.normalize_or_zero() * spacing;
+        enemy.body.collider.rotation = (-delta).arg();
+        enemy.body.velocity = ahead.body.velocity;
+    }
+
+    pub fn painter(
+        &mut self,
+        body: &mut PhysicsBody,
+        stats: &EnemyConfig,
+        painter: &mut PainterAI,
+        damage_multiplier: R32,
+        delta_time: Time,
+    ) {
+        let mut rng = thread_rng();
+        let position = body.collider.position;
+        let player = self.player.body.collider.position;
+
+        // Keep the distance
+        let target = player + (position - player).normalize_or_zero() * painter.preferred_distance;
+        let target = self.navigate(position, target);
+        let target_velocity = (target - position).normalize_or_zero() * stats.speed;
+        body.velocity +=
+            (target_velocity - body.velocity).clamp_len(..=stats.acceleration * delta_time);
+        body.move_rotation();
+
+        painter.cooldown.change(-delta_time);
+        if painter.cooldown.is_above_min() {
+            return;
+        }
+        painter.cooldown.set_ratio(Time::ONE);
+
+        let Some((_, room)) = self.rooms.iter().find(|(_, room)| room.contains(position)) else {
+            return;
+        };
+        let area = room.area.extend_uniform(r32(-1.0));
+        for _ in 0..painter.strokes {
+            let Some(&pattern) = painter.patterns.choose(&mut rng) else {
+                break;
+            };
+            let points = paint_pattern(pattern, area, player, &mut rng);
+            self.painter_strokes.push(PainterStroke {
+                points: smooth_chain(points),
+                width: painter.stroke_width,
+                damage: painter.stroke_damage * damage_multiplier,
+                blocking: rng.gen_bool(painter.blocking_chance.as_f32().clamp(0.0, 1.0).into()),
+                telegraph: Bounded::new_max(painter.telegraph),
+                lifetime: Bounded::new_max(painter.duration),
+            });
+        }
+        self.events.push(Event::Sound(SoundEvent::Drawing));
+    }
+
+    pub fn update_painter_strokes(&mut self, delta_time: Time) {
+        let player = &mut self.player;
+        let player_radius =
+            player.body.collider.compute_aabb().size().len() / r32(std::f32::consts::SQRT_2 * 2.0);
+        self.painter_strokes.retain_mut(|stroke| {
+            if stroke.telegraph.is_above_min() {
+                stroke.telegraph.change(-delta_time);
+                return true;
+            }
+
+            stroke.lifetime.change(-delta_time);
+            let hit = delta_to_chain(player.body.collider.position, &stroke.points)
+                .map_or(false, |delta| delta.len() < stroke.width + player_radius);
+            if hit && player.invincibility.is_min() && player.health.is_above_min() {
+                player.health.change(-stroke.damage);
+                player.last_hit = self.game_time;
+                player
+                    .invincibility
+                    .set(player.stats.hurt_invincibility_time);
+                self.particles_queue.push(SpawnParticles {
+                    kind: ParticleKind::HitSelf,
+                    distribution: ParticleDistribution::Circle {
+                        center: player.body.collider.position,
+                        radius: r32(0.6),
+                    },
+                    ..default()
+                });
+                self.events.push(Event::Sound(SoundEvent::HitSelf));
+            }
+
+            stroke.lifetime.is_above_min()
+        });
+    }
+}
+
+fn paint_pattern(
+    pattern: PainterPattern,
+    area: Aabb2<Coord>,
+    target: Position,
+    rng: &mut impl Rng,
+) -> Vec<Position> {
+    let clamp = |pos: Position| {
+        vec2(
+            pos.x.clamp(area.min.x, area.max.x),
+            pos.y.clamp(area.min.y, area.max.y),
+        )
+    };
+    match pattern {
+        PainterPattern::Scribble => {
+            let start = vec2(
+                rng.gen_range(area.min.x..=area.max.x),
+                rng.gen_range(area.min.y..=area.max.y),
+            );
+            let points = 6;
+            (0..=points)
+                .map(|i| {
+                    let t = r32(i as f32 / points as f32);
+                    let pos = start + (target - start) * t;
+                    clamp(rng.gen_circle(pos, r32(2.0)))
+                })
+                .collect()
+        }
+        PainterPattern::Sweep => {
+            let dir = Angle::from_degrees(r32(rng.gen_range(0.0..360.0))).unit_vec();
+            let reach = area.size().len();
+            vec![
+                clamp(target - dir * reach),
+                target,
+                clamp(target + dir * reach),
+            ]
+        }
+        PainterPattern::Cage => {
+            let radius = r32(3.0);
+            let points = 12;
+            (0..=points)
+                .map(|i| {
+                    let angle = Angle::from_degrees(r32(360.0 * i as f32 / points as f32));
+                    clamp(target + angle.unit_vec() * radius)
+                })
+                .collect()
+        }
+    }
+}
@@ -19,6 +19,23 @@ impl Model {
             });
         }
 
+        // Status effects
+        let status_damage = player.status.update(delta_time);
+        if status_damage > Hp::ZERO {
+            player.health.change(-status_damage);
+        }
+        for effect in &player.status.effects {
+            self.particles_queue.push(SpawnParticles {
+                kind: ParticleKind::Status(effect.kind),
+                distribution: ParticleDistribution::Circle {
+                    center: player.body.collider.position,
+                    radius: r32(0.4),
+                },
+                density: r32(20.0) * delta_time,
+                ..default()
+            });
+        }
+
         if player.health.is_min() {
             player.body.collider.position += player.body.velocity * delta_time;
             player.body.move_rotation();
@@ -31,7 +48,7 @@ impl Model {
             player.body.velocity = vec2::ZERO;
         } else {
             let move_dir = input.move_dir.clamp_len(..=Coord::ONE);
-            let target_velocity = move_dir * player.stats.speed;
+            let target_velocity = move_dir * player.stats.speed * player.status.speed_multiplier();
             player.body.velocity += (target_velocity - player.body.velocity)
                 .clamp_len(..=player.stats.acceleration * delta_time);
         }
@@ -40,12 +57,7 @@ impl Model {
         player.body.collider.rotation = (self.cursor_pos - player.body.collider.position).arg()
             + Angle::from_degrees(30.0).map(r32);
 
-        let stats = match player.active_weapon {
-            Weapon::Whip => &mut player.stats.whip,
-            Weapon::Dash => &mut player.stats.dash,
-            Weapon::Bow => &mut player.stats.bow,
-            Weapon::FishingRod => &mut player.stats.fishing,
-        };
+        let stats = player.stats.weapon_mut(player.active_weapon);
         let ready = stats.cooldown.is_min();
         stats.cooldown.change(-delta_time);
         if !ready && stats.cooldown.is_min() {
@@ -59,6 +71,11 @@ impl Model {
             });
         }
 
+        if player.status.is_stunned() {
+            player.draw_action = None;
+            return;
+        }
+
         match input.drawing {
             Some(position) => {
                 // Drawing
@@ -87,21 +104,31 @@ impl Model {
                 let inside = self
                     .rooms
                     .iter()
-                    .any(|(_, room)| room.area.contains(point.position));
+                    .any(|(_, room)| room.contains(point.position));
                 let direction = self
                     .rooms
                     .iter()
-                    .find(|(_, room)| room.area.contains(player.body.collider.position))
+                    .find(|(_, room)| room.contains(player.body.collider.position))
                     .map(|(_, room)| (room, room.closest_wall(point.position)));
                 let can_expand = can_expand
                     && direction.map_or(false, |(room, (_, direction))| {
-                        room.expanded_direction.is_none()
+                        // Only expand through the outer walls
+                        !room.area.contains(point.position)
+                            && room.expanded_direction.is_none()
                             && room
                                 .unlocked_after
                                 .map_or(true, |(_, dir)| dir != direction)
                     });
 
-                if remaining > Coord::ZERO && (inside || can_expand) {
+                // Painter strokes block the drawing
+                let blocked = self.painter_strokes.iter().any(|stroke| {
+                    stroke.blocking
+                        && stroke.is_active()
+                        && delta_to_chain(point.position, &stroke.points)
+                            .map_or(false, |delta| delta.len() < stroke.width)
+                });
+
+                if remaining > Coord::ZERO && (inside || can_expand) && !blocked {
                     // Add a point
                     let last = drawing
                         .points_raw
@@ -113,19 +140,8 @@ impl Model {
                     drawing.points_raw.push(point);
 
                     // Update smooth
-                    let points: Vec<_> = drawing
-                        .points_raw
-                        .iter()
-                        .map(|point| point.position.as_f32())
-                        .dedup_by(|a, b| (*a - *b).len_sqr() < 0.01)
-                        .collect();
-                    let chain = if points.len() < 3 {
-                        Chain::new(points)
-                    } else {
-                        CardinalSpline::new(points, 0.5).chain(3)
-                    };
                     drawing.points_smoothed =
-                        chain.vertices.into_iter().map(|pos| pos.as_r32()).collect();
+                        smooth_chain(drawing.points_raw.iter().map(|point| point.position));
                 }
 
                 self.particles_queue.push(SpawnParticles {
@@ -156,20 +172,40 @@ impl Model {
         }
 
         let can_expand = self.can_expand();
+        // Pacifists only hurt enemies with barrels
+        let pacifist = self.options.mode == GameMode::Pacifist;
+        let damage_multiplier = if pacifist { Hp::ZERO } else { Hp::ONE };
+        let echo = self.player.has_upgrade(&UpgradeEffect::Echo);
+        let trail = self.player.has_upgrade(&UpgradeEffect::Trail);
+        let pierce = self.player.has_upgrade(&UpgradeEffect::Pierce);
+        let reverb = self.player.has_synergy(Synergy::Reverb);
+        let afterburn = self.player.has_synergy(Synergy::Afterburn);
+        let plague = self.player.has_synergy(Synergy::Plague);
+        // Wildfire trails burn like the whip
+        let wildfire = self
+            .player
+            .stats
+            .whip
+            .effects
+            .iter()
+            .find(|effect| effect.kind == StatusKind::Burn)
+            .filter(|_| self.player.has_synergy(Synergy::Wildfire))
+            .cloned();
 
         let player = &mut self.player;
-        let stats = match player.active_weapon {
-            Weapon::Whip => &mut player.stats.whip,
-            Weapon::Dash => &mut player.stats.dash,
-            Weapon::Bow => &mut player.stats.bow,
-            Weapon::FishingRod => &mut player.stats.fishing,
+        let stats = player.stats.weapon_mut(player.active_weapon);
+        let effects = if pacifist {
+            Vec::new()
+        } else {
+            stats.effects.clone()
         };
+        let legendaries = &self.config.legendaries;
 
         let expand_room = can_expand
             .then(|| {
                 self.rooms
                     .iter()
-                    .find(|(_, room)| room.area.contains(player.body.collider.position))
+                    .find(|(_, room)| room.contains(player.body.collider.position))
                     .map(|(idx, _)| idx)
             })
             .flatten();
@@ -184,8 +220,40 @@ impl Model {
         stats.cooldown.set_ratio(Time::ONE);
 
         match player.active_weapon {
-            Weapon::Whip => {}
+            Weapon::Whip => {
+                if echo {
+                    let width = if reverb {
+                        stats.width * legendaries.reverb_width
+                    } else {
+                        stats.width
+                    };
+                    self.echoes.push(WhipEcho {
+                        points: drawing.points_smoothed.clone(),
+                        width,
+                        damage: stats.damage * legendaries.echo_damage * damage_multiplier,
+                        effects: effects.clone(),
+                        delay: legendaries.echo_delay,
+                    });
+                }
+            }
             Weapon::Dash => {
+                if trail {
+                    let duration = if afterburn {
+                        legendaries.trail_duration * legendaries.afterburn_duration
+                    } else {
+                        legendaries.trail_duration
+                    };
+                    let mut trail_effects = effects.clone();
+                    trail_effects.extend(wildfire.filter(|_| !pacifist));
+                    self.trails.push(DashTrail {
+                        points: drawing.points_smoothed.clone(),
+                        width: legendaries.trail_width,
+                        damage: stats.damage * legendaries.trail_damage * damage_multiplier,
+                        effects: trail_effects,
+                        affected: Vec::new(),
+                        lifetime: Bounded::new_max(duration),
+                    });
+                }
                 player.body.collider.position = last;
                 player.body.velocity = (last - prelast).normalize_or_zero() * stats.speed;
             }
@@ -194,9 +262,13 @@ impl Model {
                     health: Bounded::new_max(r32(1.0)),
                     body: PhysicsBody::new(last, Shape::circle(0.3)),
                     ai: MinionAI::Bullet {
-                        damage: stats.damage,
-                        explosion_damage: stats.damage * r32(1.5),
+                        damage: stats.damage * damage_multiplier,
+                        explosion_damage: stats.damage * r32(1.5) * damage_multiplier,
                         explosion_radius: stats.width * r32(2.0),
+                        effects: effects.clone(),
+                        explosion_effects: if plague { effects.clone() } else { Vec::new() },
+                        pierce,
+                        hit: Vec::new(),
                     },
                 };
                 bullet.body.velocity = (last - prelast).normalize_or_zero() * stats.speed;
@@ -206,11 +278,12 @@ impl Model {
         }
 
         let width = stats.width;
-        let damage = stats.damage;
-        self.damage_around(drawing, width, damage);
+        let damage = stats.damage * damage_multiplier;
+        self.collect_drawn_upgrades(&drawing, width);
+        self.damage_around(drawing, width, damage, &effects);
 
         if let Some(room) = expand_room {
-            if !self.rooms.iter().any(|(_, room)| room.area.contains(last)) {
+            if !self.rooms.iter().any(|(_, room)| room.contains(last)) {
                 self.unlock_room(room, last);
             }
         }
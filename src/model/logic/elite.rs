@@ -0,0 +1,112 @@
+use super::*;
+
+impl Model {
+    /// Apply the passive effects of the elite modifiers.
+    pub fn elite_modifiers(&mut self, enemy: &mut Enemy, delta_time: Time) {
+        let position = enemy.body.collider.position;
+        for modifier in &mut enemy.elite {
+            match modifier {
+                EliteModifier::Regenerating { rate } => {
+                    let heal = enemy.health.max() * *rate * delta_time;
+                    enemy.health.change(heal);
+                }
+                EliteModifier::ShieldedAura { range, cooldown } => {
+                    cooldown.change(-delta_time);
+                    if cooldown.is_min() {
+                        cooldown.set_ratio(Time::ONE);
+                        for other in &mut self.enemies {
+                            if (other.body.collider.position - position).len() < *range {
+                                other.invincibility.set_ratio(Time::ONE);
+                            }
+                        }
+                        self.particles_queue.push(SpawnParticles {
+                            kind: ParticleKind::Shield,
+                            density: r32(1.0),
+                            distribution: ParticleDistribution::Circle {
+                                center: position,
+                                radius: *range,
+                            },
+                            ..default()
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Trigger the on-death effects of the elite modifiers.
+    pub fn elite_death(&mut self, enemy: &Enemy) {
+        let position = enemy.body.collider.position;
+        for modifier in &enemy.elite {
+            match *modifier {
+                EliteModifier::Splitting { count } => {
+                    for i in 0..count {
+                        let dir =
+                            Angle::from_degrees(r32(360.0 * i as f32 / count as f32)).unit_vec();
+                        let mut split = Enemy::new(
+                            self.id_gen.gen(),
+                            EnemyConfig {
+                                health: enemy.health.max() / r32(2.0),
+                                score: enemy.stats.score.map(|score| score / 2),
+                                shape: enemy.stats.shape.scaled(r32(0.7)),
+                                ..enemy.stats.clone()
+                            },
+                            position + dir * r32(0.5),
+                        );
+                        split.body.velocity = dir * split.stats.speed;
+                        self.spawn_queue.push(split);
+                    }
+                }
+                EliteModifier::Explosive { range, damage } => {
+                    self.hostile_explosion(position, range, damage);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Queue an explosion hurting the player.
+    pub fn hostile_explosion(&mut self, position: Position, range: Coord, damage: Hp) {
+        self.explosions.push(Explosion {
+            position,
+            range,
+            damage,
+            effects: Vec::new(),
+            source: ExplosionSource::Hostile,
+        });
+    }
+}
+
+/// Pick random elite modifiers fitting into the difficulty budget.
+pub fn roll_elite(
+    config: &EliteConfig,
+    chance: R32,
+    mut budget: R32,
+    rng: &mut impl Rng,
+) -> Vec<EliteModifierConfig> {
+    let mut chosen: Vec<EliteModifierConfig> = Vec::new();
+    if !rng.gen_bool(chance.as_f32().clamp(0.0, 1.0).into()) {
+        return chosen;
+    }
+
+    while chosen.len() < config.max_modifiers {
+        let Some(modifier) = config
+            .modifiers
+            .iter()
+            .filter(|modifier| {
+                modifier.cost <= budget
+                    && !chosen.iter().any(|other| {
+                        std::mem::discriminant(&other.modifier)
+                            == std::mem::discriminant(&modifier.modifier)
+                    })
+            })
+            .choose(rng)
+        else {
+            break;
+        };
+        budget -= modifier.cost;
+        chosen.push(modifier.clone());
+    }
+    chosen
+}
@@ -0,0 +1,95 @@
+use super::*;
+
+impl Model {
+    pub fn process_explosions(&mut self) {
+        let config = self.config.explosions.clone();
+        for explosion in std::mem::take(&mut self.explosions) {
+            let collider = Collider::new(explosion.position, Shape::circle(explosion.range));
+            // 1 at the center, 0 at the edge
+            let closeness = |position: Position| {
+                R32::ONE - ((position - explosion.position).len() / explosion.range).min(R32::ONE)
+            };
+
+            // Knockback
+            let push = |body: &mut PhysicsBody| {
+                if !collider.check(&body.collider) {
+                    return false;
+                }
+                let dir = (body.collider.position - explosion.position).normalize_or_zero();
+                body.velocity += dir * config.knockback * closeness(body.collider.position)
+                    / body.mass.max(r32(0.1));
+                true
+            };
+            push(&mut self.player.body);
+            for enemy in &mut self.enemies {
+                if push(&mut enemy.body) {
+                    enemy.last_knockback = self.game_time;
+                }
+            }
+            for minion in &mut self.minions {
+                push(&mut minion.body);
+            }
+
+            // Player
+            let player = &mut self.player;
+            if explosion.source.hurts_player()
+                && player.invincibility.is_min()
+                && collider.check(&player.body.collider)
+            {
+                let falloff = config.player_falloff
+                    + (R32::ONE - config.player_falloff) * closeness(player.body.collider.position);
+                let mut damage = explosion.damage * falloff;
+                if let ExplosionSource::Object = explosion.source {
+                    damage *= config.object_player_damage;
+                }
+                if damage > Hp::ZERO {
+                    player.health.change(-damage);
+                    player.last_hit = self.game_time;
+                    player
+                        .invincibility
+                        .set(player.stats.hurt_invincibility_time);
+                    self.events.push(Event::Sound(SoundEvent::HitSelf));
+                }
+            }
+
+            // Enemies
+            if explosion.source.hurts_enemies() {
+                for enemy in &mut self.enemies {
+                    if enemy.invincibility.is_min() && collider.check(&enemy.body.collider) {
+                        enemy.take_damage(explosion.damage);
+                        for effect in &explosion.effects {
+                            enemy.status.apply(effect);
+                        }
+                    }
+                }
+            }
+
+            // Chain reaction
+            for object in &mut self.objects {
+                if object.kind.is_explosive()
+                    && !object.dead
+                    && object.fuse.is_none()
+                    && collider.check(&object.collider)
+                {
+                    object.fuse = Some(config.chain_delay);
+                }
+            }
+
+            let kind = match explosion.effects.first() {
+                Some(effect) if explosion.damage == Hp::ZERO => ParticleKind::Status(effect.kind),
+                _ => ParticleKind::Damage,
+            };
+            self.particles_queue.push(SpawnParticles {
+                kind,
+                distribution: ParticleDistribution::Circle {
+                    center: explosion.position,
+                    radius: explosion.range,
+                },
+                ..default()
+            });
+            if explosion.source != ExplosionSource::Bullet {
+                self.events.push(Event::Sound(SoundEvent::Explosion));
+            }
+        }
+    }
+}
@@ -1,19 +1,49 @@
+mod boss;
 mod controls;
+mod elite;
+mod explosion;
+mod navigation;
+mod object;
+mod obstacle;
+mod raycast;
+mod room;
+mod upgrade;
+mod wave;
 
 use std::collections::BTreeMap;
 
+use self::elite::roll_elite;
+
 use super::*;
 
 impl Model {
     pub fn update(&mut self, input: PlayerControls, delta_time: Time) {
+        let game_over = self.is_game_over();
+        if game_over && self.player.health.is_above_min() {
+            // The run is finished
+            return;
+        }
+
         self.real_time += delta_time;
         self.game_time += delta_time;
 
-        if !self.rooms.contains(Index::from_raw_parts(0, 0)) || self.rooms.len() > 1 {
+        if !self.in_starting_room() {
             // Exited the starting room
             self.difficulty_raw += self.config.difficulty.time_scaling * delta_time;
             let difficulty_step = r32(1.0);
             self.difficulty = (self.difficulty_raw / difficulty_step).floor() * difficulty_step;
+
+            if let GameMode::TimeAttack = self.options.mode {
+                self.time_left = (self.time_left - delta_time).max(Time::ZERO);
+            }
+        }
+        if let GameMode::Pacifist = self.options.mode {
+            self.restock_barrels();
+        }
+
+        let mut input = input;
+        if self.has_mutator(&Mutator::MirrorControls) {
+            input.move_dir.x = -input.move_dir.x;
         }
 
         if self.player.health.is_min() {
@@ -24,19 +54,44 @@ impl Model {
             self.events.push(Event::Sound(SoundEvent::Drawing));
         }
 
+        if let Some(encounter) = &mut self.boss_encounter {
+            encounter.intro.change(-delta_time);
+        }
+
         self.compress_rooms(delta_time);
         self.controls(input, delta_time);
         self.enemy_ai(delta_time);
+        self.update_painter_strokes(delta_time);
+        self.update_legendaries(delta_time);
         self.minion_ai(delta_time);
+        self.update_objects(delta_time);
         self.collisions(delta_time);
         self.passive_particles(delta_time);
         self.check_deaths(delta_time);
+        self.process_explosions();
         self.update_camera(delta_time);
+        self.update_challenge(delta_time);
+        self.update_waves(delta_time);
         self.process_spawns(delta_time);
+
+        if !game_over && self.is_game_over() {
+            self.events.push(Event::GameOver);
+        }
     }
 
     pub fn can_expand(&self) -> bool {
-        self.enemies.is_empty()
+        self.room_clear() && self.challenge.is_none()
+    }
+
+    /// Whether the wall can be broken to unlock a new room.
+    pub fn is_breakable_wall(&self, room_idx: Index, direction: Direction) -> bool {
+        self.can_expand()
+            && self.rooms.get(room_idx).map_or(false, |room| {
+                room.expanded_direction.is_none()
+                    && room
+                        .unlocked_after
+                        .map_or(true, |(_, dir)| dir != direction)
+            })
     }
 
     pub fn process_spawns(&mut self, _delta_time: Time) {
@@ -45,16 +100,8 @@ impl Model {
     }
 
     pub fn passive_particles(&mut self, delta_time: Time) {
-        let can_expand = self.can_expand();
         for (index, direction, collider) in &self.room_colliders {
-            let can_expand = can_expand
-                && self.rooms.get(*index).map_or(false, |room| {
-                    room.expanded_direction.is_none()
-                        && room
-                            .unlocked_after
-                            .map_or(true, |(_, dir)| dir != *direction)
-                });
-            let kind = if can_expand {
+            let kind = if self.is_breakable_wall(*index, *direction) {
                 ParticleKind::WallBreakable
             } else {
                 ParticleKind::WallBlock
@@ -70,51 +117,103 @@ impl Model {
     }
 
     pub fn update_camera(&mut self, delta_time: Time) {
-        if self.rooms.contains(Index::from_raw_parts(0, 0)) && self.rooms.len() == 1
-            || self.player.draw_action.is_some()
-        {
+        if self.in_starting_room() || self.player.draw_action.is_some() {
             return;
         }
 
-        let offset = (self.player.body.collider.position - self.camera.center.as_r32()) / r32(0.5)
-            * delta_time;
+        let player = self.player.body.collider.position;
+        let (target, fov) = if self.boss_encounter.is_some() {
+            // Keep both the player and the bosses in view
+            let view = Aabb2::points_bounding_box(
+                std::iter::once(player).chain(
+                    self.enemies
+                        .iter()
+                        .filter(|enemy| enemy.is_boss)
+                        .map(|enemy| enemy.body.collider.position),
+                ),
+            )
+            .unwrap_or(Aabb2::point(player));
+            let aspect = crate::GAME_RESOLUTION.as_f32().aspect();
+            let fov = (view.height().as_f32().max(view.width().as_f32() / aspect) + 10.0)
+                .clamp(CAMERA_FOV, CAMERA_FOV * 1.5);
+            (view.center(), fov)
+        } else {
+            (player, CAMERA_FOV)
+        };
+
+        let offset = (target - self.camera.center.as_r32()) / r32(0.5) * delta_time;
         self.camera.center += offset.as_f32();
+        self.camera.fov += (fov - self.camera.fov) / 0.5 * delta_time.as_f32();
     }
 
-    pub fn collisions(&mut self, _delta_time: Time) {
+    pub fn collisions(&mut self, delta_time: Time) {
         // Object collisions
         for object in &mut self.objects {
             let player = &mut self.player;
-            if let Some(collision) = player.body.collider.collide(&object.collider) {
-                player.body.collider.position -= collision.normal * collision.penetration;
-                let projection = vec2::dot(player.body.velocity, collision.normal);
-                player.body.velocity -= collision.normal * projection;
-                if projection > r32(1.0) {
-                    self.events.push(Event::Sound(SoundEvent::Bounce));
+            if let ObjectKind::Crate = object.kind {
+                if let Some(collision) = player.body.collider.collide(&object.collider) {
+                    // Push the crate, unless it is stuck against a wall
+                    object.collider.position += collision.normal * collision.penetration;
+                    for (_, _, wall) in &self.room_colliders {
+                        if let Some(collision) = object.collider.collide(wall) {
+                            object.collider.position -= collision.normal * collision.penetration;
+                        }
+                    }
+                }
+            }
+            if object.kind.is_solid() {
+                if let Some(collision) = player.body.collider.collide(&object.collider) {
+                    player.body.collider.position -= collision.normal * collision.penetration;
+                    let projection = vec2::dot(player.body.velocity, collision.normal);
+                    player.body.velocity -= collision.normal * projection;
+                    if projection > r32(1.0) {
+                        self.events.push(Event::Sound(SoundEvent::Bounce));
+                    }
                 }
             }
 
             for minion in &mut self.minions {
                 if minion.body.collider.check(&object.collider) {
                     match (&minion.ai, &object.kind) {
-                        (MinionAI::Bullet { .. }, ObjectKind::ExplosiveBarrel { .. }) => {
+                        (
+                            MinionAI::Bullet { .. },
+                            ObjectKind::ExplosiveBarrel { .. } | ObjectKind::FreezeBarrel { .. },
+                        ) => {
                             // NOTE: explosion managed on death
                             minion.health.set_ratio(Hp::ZERO);
                             object.dead = true;
                             self.events.push(Event::Sound(SoundEvent::Hit));
                         }
+                        (
+                            MinionAI::Bullet { .. },
+                            ObjectKind::Turret { .. } | ObjectKind::Crate,
+                        ) => {
+                            minion.health.set_ratio(Hp::ZERO);
+                        }
+                        _ => {}
                     }
                 }
             }
 
             for enemy in &mut self.enemies {
-                if enemy.body.collider.check(&object.collider) {
+                if let Some(collision) = enemy.body.collider.collide(&object.collider) {
                     match &object.kind {
-                        ObjectKind::ExplosiveBarrel { .. } => {
+                        ObjectKind::ExplosiveBarrel { .. } | ObjectKind::FreezeBarrel { .. } => {
                             // NOTE: explosion managed on death
                             object.dead = true;
                             self.events.push(Event::Sound(SoundEvent::Hit));
                         }
+                        ObjectKind::Turret { .. } | ObjectKind::Crate => {
+                            enemy.body.collider.position -=
+                                collision.normal * collision.penetration;
+                        }
+                        _ => {}
+                    }
+                    if let EnemyAI::Bullet = enemy.ai {
+                        if object.kind.is_solid() {
+                            // Objects block the shots
+                            enemy.health.set_ratio(Hp::ZERO);
+                        }
                     }
                 }
             }
@@ -150,6 +249,9 @@ impl Model {
                 if damage > Hp::ZERO {
                     player.health.change(-damage);
                     player.last_hit = self.game_time;
+                    for effect in &enemy.stats.effects {
+                        player.status.apply(effect);
+                    }
                     player
                         .invincibility
                         .set(player.stats.hurt_invincibility_time);
@@ -166,6 +268,7 @@ impl Model {
 
                 enemy.body.collider.position += correction * enemy_t;
                 enemy.body.velocity += bounce * enemy_t;
+                enemy.last_knockback = self.game_time;
 
                 if let EnemyAI::Bullet = enemy.ai {
                     enemy.health.set_ratio(Hp::ZERO);
@@ -189,12 +292,28 @@ impl Model {
         for minion in &mut self.minions {
             for enemy in &mut self.enemies {
                 if minion.body.collider.check(&enemy.body.collider) {
-                    match minion.ai {
-                        MinionAI::Bullet { damage, .. } => {
-                            // NOTE: explosion managed on death
-                            minion.health.set_ratio(Hp::ZERO);
+                    match &mut minion.ai {
+                        MinionAI::Bullet {
+                            damage,
+                            effects,
+                            pierce,
+                            hit,
+                            ..
+                        } => {
+                            if *pierce {
+                                if hit.contains(&enemy.id) {
+                                    continue;
+                                }
+                                hit.push(enemy.id);
+                            } else {
+                                // NOTE: explosion managed on death
+                                minion.health.set_ratio(Hp::ZERO);
+                            }
                             if enemy.invincibility.is_min() {
-                                enemy.health.change(-damage);
+                                enemy.take_damage(*damage);
+                                for effect in effects {
+                                    enemy.status.apply(effect);
+                                }
                             }
                             self.events.push(Event::Sound(SoundEvent::Hit));
                         }
@@ -257,12 +376,14 @@ impl Model {
             }
         }
 
+        self.obstacle_collisions(delta_time);
+
         // Out of bounds
         let player = &mut self.player;
         if self
             .rooms
             .iter()
-            .all(|(_, room)| !room.area.contains(player.body.collider.position))
+            .all(|(_, room)| !room.contains(player.body.collider.position))
         {
             if let Some((distance, direction)) = self
                 .rooms
@@ -276,34 +397,25 @@ impl Model {
     }
 
     pub fn collect_upgrade(&mut self, upgrade: Upgrade) {
-        match upgrade.effect {
-            UpgradeEffect::Width => {
-                self.player.stats.whip.width += r32(0.5);
-                self.player.stats.dash.width += r32(0.5);
-                self.player.stats.bow.width += r32(0.2);
-            }
-            UpgradeEffect::Range => {
-                self.player.stats.whip.max_distance += r32(3.0);
-                self.player.stats.dash.max_distance += r32(3.0);
-                self.player.stats.bow.max_distance += r32(3.0);
-            }
-            UpgradeEffect::Damage => {
-                self.player.stats.whip.damage += r32(3.0);
-                self.player.stats.dash.damage += r32(3.0);
-                self.player.stats.bow.damage += r32(3.0);
-            }
-            UpgradeEffect::Speed => {
-                self.player.stats.speed += r32(1.0);
-                self.player.stats.acceleration += r32(2.5);
-            }
+        match &upgrade.effect {
             UpgradeEffect::Difficulty => {
                 self.difficulty_raw += self.config.difficulty.upgrade_amount;
                 self.score_multiplier += self.config.score.upgrade_multiplier;
             }
-            UpgradeEffect::Weapon(weapon) => {
+            &UpgradeEffect::Weapon(weapon) => {
                 self.player.active_weapon = weapon;
             }
+            effect @ UpgradeEffect::Health => {
+                let before = self.player.stats.health;
+                upgrade_stats(&mut self.player.stats, effect);
+                let gain = self.player.stats.health - before;
+                let health = &mut self.player.health;
+                *health = Health::new(health.value() + gain, Hp::ZERO..=self.player.stats.health);
+            }
+            effect => upgrade_stats(&mut self.player.stats, effect),
         };
+        self.player.upgrades.push(upgrade.effect.clone());
+        self.update_synergies();
         self.particles_queue.push(SpawnParticles {
             kind: ParticleKind::Upgrade,
             distribution: ParticleDistribution::Circle {
@@ -320,29 +432,33 @@ impl Model {
                 && self
                     .rooms
                     .iter()
-                    .any(|(_, room)| room.area.contains(object.collider.position));
+                    .any(|(_, room)| room.contains(object.collider.position));
             if !alive {
-                match &object.kind {
-                    &ObjectKind::ExplosiveBarrel { range, damage } => {
-                        let explosion =
-                            Collider::new(object.collider.position, Shape::circle(range));
-                        for enemy in &mut self.enemies {
-                            if enemy.invincibility.is_min() && explosion.check(&enemy.body.collider)
-                            {
-                                enemy.health.change(-damage);
-                            }
-                        }
-
-                        self.particles_queue.push(SpawnParticles {
-                            kind: ParticleKind::Damage,
-                            distribution: ParticleDistribution::Circle {
-                                center: object.collider.position,
-                                radius: range,
-                            },
-                            ..default()
+                // NOTE: processed after the deaths
+                match object.kind {
+                    ObjectKind::ExplosiveBarrel { range, damage } => {
+                        self.explosions.push(Explosion {
+                            position: object.collider.position,
+                            range,
+                            damage,
+                            effects: Vec::new(),
+                            source: ExplosionSource::Object,
+                        });
+                    }
+                    ObjectKind::FreezeBarrel { range, stun } => {
+                        self.explosions.push(Explosion {
+                            position: object.collider.position,
+                            range,
+                            damage: Hp::ZERO,
+                            effects: vec![StatusEffectConfig {
+                                kind: StatusKind::Stun,
+                                duration: stun,
+                                magnitude: R32::ZERO,
+                            }],
+                            source: ExplosionSource::Object,
                         });
-                        self.events.push(Event::Sound(SoundEvent::Explosion));
                     }
+                    _ => {}
                 }
             }
             alive
@@ -355,27 +471,18 @@ impl Model {
                     MinionAI::Bullet {
                         explosion_damage,
                         explosion_radius,
+                        ref explosion_effects,
                         ..
                     } => {
-                        let explosion = Collider::new(
-                            minion.body.collider.position,
-                            Shape::circle(explosion_radius),
-                        );
-                        for enemy in &mut self.enemies {
-                            if enemy.invincibility.is_min() && explosion.check(&enemy.body.collider)
-                            {
-                                enemy.health.change(-explosion_damage);
-                            }
+                        if explosion_radius > Coord::ZERO {
+                            self.explosions.push(Explosion {
+                                position: minion.body.collider.position,
+                                range: explosion_radius,
+                                damage: explosion_damage,
+                                effects: explosion_effects.clone(),
+                                source: ExplosionSource::Bullet,
+                            });
                         }
-
-                        self.particles_queue.push(SpawnParticles {
-                            kind: ParticleKind::Damage,
-                            distribution: ParticleDistribution::Circle {
-                                center: minion.body.collider.position,
-                                radius: explosion_radius,
-                            },
-                            ..default()
-                        });
                     }
                 }
             }
@@ -383,13 +490,34 @@ impl Model {
         });
 
         let in_battle = !self.enemies.is_empty() || !self.spawn_queue.is_empty();
+        let exploding = self
+            .options
+            .mutators
+            .iter()
+            .find_map(|mutator| match *mutator {
+                Mutator::ExplodingEnemies { range, damage } => Some((range, damage)),
+                _ => None,
+            });
+        let mut elite_deaths = Vec::new();
+        let mut explosions = Vec::new();
         self.enemies.retain(|enemy| {
             let alive = enemy.health.is_above_min();
             if !alive {
+                if enemy.is_elite() {
+                    elite_deaths.push(enemy.clone());
+                }
+                if let Some((range, damage)) = exploding {
+                    if !matches!(enemy.ai, EnemyAI::Bullet) {
+                        explosions.push((enemy.body.collider.position, range, damage));
+                    }
+                }
                 if enemy.is_boss {
                     self.bosses_killed += 1;
+                    self.rerolls += self.config.upgrade_choices.boss_rerolls;
+                    self.banishes += self.config.upgrade_choices.boss_banishes;
                 }
-                if self.player.health.is_above_min() {
+                // NOTE: pacifists only score from rooms
+                if self.player.health.is_above_min() && self.options.mode != GameMode::Pacifist {
                     self.score += (enemy.stats.score.unwrap_or(0) as f32
                         * self.score_multiplier.as_f32())
                         as Score;
@@ -409,7 +537,20 @@ impl Model {
             }
             alive
         });
-        if in_battle && self.enemies.is_empty() && self.player.health.is_above_min() {
+        for enemy in elite_deaths {
+            self.elite_death(&enemy);
+        }
+        for (position, range, damage) in explosions {
+            self.hostile_explosion(position, range, damage);
+        }
+        if self.boss_encounter.is_some() && !self.enemies.iter().any(|enemy| enemy.is_boss) {
+            self.boss_encounter = None;
+        }
+        if in_battle
+            && self.room_clear()
+            && self.challenge.is_none()
+            && self.player.health.is_above_min()
+        {
             self.finish_battle();
         }
 
@@ -425,53 +566,46 @@ impl Model {
         let Some((_, room)) = self
             .rooms
             .iter()
-            .find(|(_, room)| room.area.contains(self.player.body.collider.position))
+            .find(|(_, room)| room.contains(self.player.body.collider.position))
         else {
             return;
         };
-        let offset = if room.area.size().aspect() > r32(0.5) {
-            vec2(2.5, 0.0).as_r32()
-        } else {
-            vec2(0.0, 2.5).as_r32()
-        };
+        let area = room.area;
+        let difficulty = room.difficulty;
+        let reward = room.reward.clone();
 
         self.pacman_1ups.clear();
+        self.snake_food.clear();
+        self.painter_strokes.clear();
 
-        let mut rng = thread_rng();
-        let mut options = vec![
-            UpgradeEffect::Width,
-            UpgradeEffect::Range,
-            UpgradeEffect::Damage,
-            UpgradeEffect::Speed,
-            UpgradeEffect::Difficulty,
-        ];
-        options.extend(
-            [Weapon::Whip, Weapon::Dash, Weapon::Bow, Weapon::FishingRod]
-                .into_iter()
-                .filter(|weapon| *weapon != self.player.active_weapon)
-                .map(UpgradeEffect::Weapon),
-        );
-        let options: Vec<_> = options
-            .choose_multiple(&mut rng, self.config.upgrades_per_level)
-            .collect();
-        let upgrades = options.iter().enumerate().map(|(i, effect)| Upgrade {
-            collider: Collider::new(
-                room.area.center() + offset * r32(i as f32 - (options.len() as f32 - 1.0) / 2.0),
-                Shape::circle(0.5),
-            ),
-            effect: (*effect).clone(),
-        });
-        self.upgrades.extend(upgrades);
+        self.spawn_upgrades(area, self.config.upgrades_per_level, None, reward);
 
         self.rooms_cleared += 1;
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        match self.options.mode {
+            GameMode::TimeAttack => self.time_left += self.config.modes.time_attack_room_bonus,
+            GameMode::BossRush => {
+                let heal = self.player.health.max() * self.config.modes.boss_rush_heal;
+                self.heal_player(heal);
+            }
+            _ => {}
+        }
+        let endless_loop = self.endless_loop();
+        if endless_loop > self.config.endless_loop(self.rooms_cleared) {
+            // New endless loop
+            if let Some(modifier) = self.config.endless.modifiers.choose(&mut rng) {
+                log::debug!("Starting endless loop {endless_loop} with {modifier:?}");
+                self.endless_modifiers.push(modifier.clone());
+            }
+        }
         self.difficulty_raw += self.config.difficulty.room_bonus
             * self
                 .config
                 .difficulty
                 .room_exponent
                 .powf(r32(self.rooms_cleared as f32));
-        self.score +=
-            (self.config.score.room_bonus as f32 * self.score_multiplier.as_f32()) as Score;
+        self.score += (self.config.score.room_bonus as f32
+            * (self.score_multiplier * difficulty).as_f32()) as Score;
     }
 
     pub fn enemy_ai(&mut self, delta_time: Time) {
@@ -487,6 +621,63 @@ impl Model {
             };
 
             enemy.invincibility.change(-delta_time);
+            if let Some(encounter) = self.boss_encounter.as_ref().filter(|_| enemy.is_boss) {
+                // Cannot be damaged during the intro
+                enemy.invulnerable = enemy.invulnerable.max(encounter.intro.value());
+            }
+            if enemy.invulnerable > Time::ZERO {
+                enemy.invulnerable -= delta_time;
+                let value = enemy.invincibility.value().max(enemy.invulnerable);
+                enemy.invincibility.set(value);
+            }
+
+            // Status effects
+            let status_damage = enemy.status.update(delta_time);
+            if status_damage > Hp::ZERO {
+                enemy.take_damage(status_damage);
+            }
+            self.elite_modifiers(&mut enemy, delta_time);
+            if enemy.is_boss {
+                self.boss_phases(&mut enemy);
+            }
+            let enemy_radius = enemy.body.collider.compute_aabb().size().len()
+                / r32(std::f32::consts::SQRT_2 * 2.0);
+            for effect in &enemy.status.effects {
+                self.particles_queue.push(SpawnParticles {
+                    kind: ParticleKind::Status(effect.kind),
+                    distribution: ParticleDistribution::Circle {
+                        center: enemy.body.collider.position,
+                        radius: enemy_radius,
+                    },
+                    density: r32(20.0) * delta_time,
+                    ..default()
+                });
+            }
+            if let EnemyAI::SnakeSegment = enemy.ai {
+                self.snake_segment(&mut enemy);
+                self.enemies.insert(enemy);
+                continue;
+            }
+
+            let boss_intro = enemy.is_boss
+                && self
+                    .boss_encounter
+                    .as_ref()
+                    .map_or(false, BossEncounter::in_intro);
+            if boss_intro {
+                enemy.body.velocity = vec2::ZERO;
+                self.enemies.insert(enemy);
+                continue;
+            }
+            if enemy.status.is_stunned() {
+                // No steering or attacks, but knockback still carries the enemy
+                enemy.body.collider.position += enemy.body.velocity * delta_time;
+                let drag = r32(0.9);
+                enemy.body.velocity *= drag;
+                self.enemies.insert(enemy);
+                continue;
+            }
+
             let repel_force = self
                 .enemies
                 .iter()
@@ -496,6 +687,12 @@ impl Model {
                         .iter()
                         .map(|object| (object.collider.position, 1.5, 5.0)),
                 )
+                .chain(
+                    self.obstacles
+                        .iter()
+                        .filter(|obstacle| obstacle.kind.is_solid())
+                        .map(|obstacle| (obstacle.collider.position, 1.5, 5.0)),
+                )
                 .map(|(other, pow, weight)| {
                     let delta = enemy.body.collider.position - other;
                     let len = delta.len();
@@ -506,6 +703,12 @@ impl Model {
                     }
                 })
                 .fold(vec2::ZERO, vec2::add);
+            let repel_force = repel_force
+                + self.avoid_obstacles(
+                    enemy.body.collider.position,
+                    enemy.body.velocity.normalize_or_zero(),
+                    enemy_radius,
+                ) * r32(5.0);
 
             match &mut enemy.ai {
                 EnemyAI::Idle => {
@@ -517,7 +720,10 @@ impl Model {
                     enemy.body.move_rotation();
                 }
                 EnemyAI::Crawler => {
-                    let target = self.player.body.collider.position;
+                    let target = self.navigate(
+                        enemy.body.collider.position,
+                        self.player.body.collider.position,
+                    );
                     let target_velocity = (target - enemy.body.collider.position + repel_force)
                         .normalize_or_zero()
                         * enemy.stats.speed;
@@ -530,8 +736,13 @@ impl Model {
                     charge,
                     bullet,
                 } => {
+                    let can_shoot = self.line_of_sight(
+                        enemy.body.collider.position,
+                        self.player.body.collider.position,
+                        RaycastFilter::ALL,
+                    );
                     charge.change(delta_time);
-                    if charge.is_max() {
+                    if charge.is_max() && can_shoot {
                         charge.set_ratio(Time::ZERO);
                         let mut bullet = Enemy::new(
                             self.id_gen.gen(),
@@ -549,10 +760,16 @@ impl Model {
                         self.spawn_queue.push(bullet);
                     }
 
-                    let target = self.player.body.collider.position
-                        + (enemy.body.collider.position - self.player.body.collider.position)
-                            .normalize_or_zero()
-                            * *preferred_distance;
+                    let target = if can_shoot {
+                        self.player.body.collider.position
+                            + (enemy.body.collider.position - self.player.body.collider.position)
+                                .normalize_or_zero()
+                                * *preferred_distance
+                    } else {
+                        // Reposition to get a clear shot
+                        self.player.body.collider.position
+                    };
+                    let target = self.navigate(enemy.body.collider.position, target);
                     let target_velocity = (target - enemy.body.collider.position + repel_force)
                         .normalize_or_zero()
                         * enemy.stats.speed;
@@ -588,13 +805,14 @@ impl Model {
                                 target,
                             )
                         })
-                        .filter(|(_, enemy)| !enemy.health.is_max())
+                        .filter(|(_, enemy)| !enemy.health.is_max() || !enemy.status.is_empty())
                         .min_by_key(|(d, _)| *d)
                     {
                         heal_target = Some(target.body.collider.position);
                         if cooldown.is_min() && distance < *range {
                             cooldown.set_ratio(Time::ONE);
                             target.health.change(target.health.max() * *heal_ratio);
+                            target.status.cleanse();
                             self.particles_queue.push(SpawnParticles {
                                 kind: ParticleKind::Heal,
                                 distribution: ParticleDistribution::Circle {
@@ -622,6 +840,7 @@ impl Model {
                                     / r32(2.0)
                         },
                     );
+                    let target = self.navigate(enemy.body.collider.position, target);
                     let target_velocity = (target - enemy.body.collider.position + repel_force)
                         .normalize_or_zero()
                         * enemy.stats.speed;
@@ -682,6 +901,7 @@ impl Model {
                                     * *preferred_distance
                         },
                     );
+                    let target = self.navigate(enemy.body.collider.position, target);
                     let target_velocity = (target - enemy.body.collider.position + repel_force)
                         .normalize_or_zero()
                         * enemy.stats.speed;
@@ -694,7 +914,7 @@ impl Model {
                         if let Some((_, room)) = self
                             .rooms
                             .iter()
-                            .find(|(_, room)| room.area.contains(enemy.body.collider.position))
+                            .find(|(_, room)| room.contains(enemy.body.collider.position))
                         {
                             // Move to the target
                             let target = match *target {
@@ -797,12 +1017,26 @@ impl Model {
                         }
                     }
                 },
+                EnemyAI::Snake { snake } => {
+                    self.snake_head(enemy.id, &mut enemy.body, &enemy.stats, snake, delta_time);
+                }
+                EnemyAI::SnakeSegment => {}
+                EnemyAI::Painter { painter } => {
+                    let damage_multiplier = enemy.stats.phase_damage_multiplier(enemy.phase);
+                    self.painter(
+                        &mut enemy.body,
+                        &enemy.stats,
+                        painter,
+                        damage_multiplier,
+                        delta_time,
+                    );
+                }
                 EnemyAI::Helicopter { helicopter } => {
                     self.events.push(Event::Sound(SoundEvent::Helicopter));
                     if let Some((_, room)) = self
                         .rooms
                         .iter()
-                        .find(|(_, room)| room.area.contains(enemy.body.collider.position))
+                        .find(|(_, room)| room.contains(enemy.body.collider.position))
                     {
                         match &mut helicopter.state {
                             HelicopterState::Idle => {
@@ -828,35 +1062,28 @@ impl Model {
                             &mut HelicopterState::Moving(target) => {
                                 let delta = target - enemy.body.collider.position;
                                 if delta.len_sqr() < r32(1.0) {
-                                    if rng.gen_bool(0.3) {
+                                    if rng.gen_bool(
+                                        helicopter.minion_chance.as_f32().clamp(0.0, 1.0).into(),
+                                    ) {
                                         // Minions
-                                        let tank = &self.config.enemies["tank"];
-                                        let circle = &self.config.enemies["circle"];
-                                        let shielder = &self.config.enemies["shielder"];
-                                        let shooter = &self.config.enemies["shooter"];
-                                        let minions = [
-                                            tank.clone(),
-                                            shooter.clone(),
-                                            circle.clone(),
-                                            shielder.clone(),
-                                            circle.clone(),
-                                            circle.clone(),
-                                            shooter.clone(),
-                                        ]
-                                        .into_iter()
-                                        .map(|mut enemy| {
-                                            enemy.health *= r32(1.5);
-                                            enemy
-                                        })
-                                        .collect();
+                                        let minions = helicopter
+                                            .minions
+                                            .iter()
+                                            .filter_map(|name| self.config.enemies.get(name))
+                                            .map(|enemy| {
+                                                let mut enemy = enemy.clone();
+                                                enemy.health *= helicopter.minion_health;
+                                                enemy
+                                            })
+                                            .collect();
                                         helicopter.state = HelicopterState::Minions {
                                             minions,
                                             delay: Bounded::new_max(r32(0.15)),
                                         };
                                     } else {
                                         helicopter.state = HelicopterState::Minigun {
-                                            timer: r32(5.0),
-                                            shot_delay: Bounded::new_max(r32(0.2)),
+                                            timer: helicopter.minigun_duration,
+                                            shot_delay: Bounded::new_max(helicopter.minigun_delay),
                                         };
                                     }
                                 } else {
@@ -883,13 +1110,16 @@ impl Model {
                                         center + vec2(1.3, 0.0).as_r32(),
                                         center - vec2(1.3, 0.0).as_r32(),
                                     ];
+                                    let target = self.player.body.collider.position;
                                     for pos in poss {
+                                        if !self.line_of_sight(pos, target, RaycastFilter::ALL) {
+                                            continue;
+                                        }
                                         let mut bullet = Enemy::new(
                                             self.id_gen.gen(),
                                             (*helicopter.minigun_bullet).clone(),
                                             pos,
                                         );
-                                        let target = self.player.body.collider.position;
                                         let dir = (target - pos).normalize_or_zero();
                                         bullet.body.velocity = dir * bullet.stats.speed;
                                         self.spawn_queue.push(bullet);
@@ -929,7 +1159,8 @@ impl Model {
                 }
             }
 
-            enemy.body.collider.position += enemy.body.velocity * delta_time;
+            enemy.body.collider.position +=
+                enemy.body.velocity * enemy.status.speed_multiplier() * delta_time;
             enemy.body.collider.rotation += enemy.body.angular_velocity * delta_time;
 
             if let Some((id, offset)) = enemy.attached_to {
@@ -969,8 +1200,34 @@ impl Model {
         }
     }
 
-    pub fn damage_around(&mut self, drawing: Drawing, width: Coord, base_damage: Hp) {
+    pub fn damage_around(
+        &mut self,
+        drawing: Drawing,
+        width: Coord,
+        base_damage: Hp,
+        effects: &[StatusEffectConfig],
+    ) {
         for enemy in &mut self.enemies {
+            if let EnemyAI::SnakeSegment = enemy.ai {
+                // Cut through the snake
+                let enemy_radius = enemy.body.collider.compute_aabb().size().len()
+                    / r32(std::f32::consts::SQRT_2 * 2.0);
+                let cut = delta_to_chain(enemy.body.collider.position, &drawing.points_smoothed)
+                    .map_or(false, |delta| delta.len() < width + enemy_radius);
+                if cut && enemy.health.is_above_min() {
+                    enemy.health.set_ratio(Hp::ZERO);
+                    self.particles_queue.push(SpawnParticles {
+                        kind: ParticleKind::Damage,
+                        distribution: ParticleDistribution::Circle {
+                            center: enemy.body.collider.position,
+                            radius: enemy_radius,
+                        },
+                        ..default()
+                    });
+                    self.events.push(Event::Sound(SoundEvent::Hit));
+                }
+                continue;
+            }
             if enemy.invincibility.is_above_min() {
                 continue;
             }
@@ -984,8 +1241,11 @@ impl Model {
             let enemy_radius = enemy.body.collider.compute_aabb().size().len()
                 / r32(std::f32::consts::SQRT_2 * 2.0);
             if delta.len() < width + enemy_radius {
-                enemy.health.change(-base_damage); // TODO: combo scaling
+                enemy.take_damage(base_damage); // TODO: combo scaling
                 enemy.last_hit = self.game_time;
+                for effect in effects {
+                    enemy.status.apply(effect);
+                }
 
                 if let Weapon::FishingRod = &self.player.active_weapon {
                     if drawing.points_smoothed.len() >= 2 {
@@ -994,6 +1254,7 @@ impl Model {
                             - drawing.points_smoothed[drawing.points_smoothed.len() - 2])
                             .normalize_or_zero();
                         enemy.body.velocity += dir * speed;
+                        enemy.last_knockback = self.game_time;
                     }
                 }
 
@@ -1018,13 +1279,33 @@ impl Model {
             let object_radius =
                 object.collider.compute_aabb().size().len() / r32(std::f32::consts::SQRT_2 * 2.0);
             if delta.len() < width + object_radius {
-                object.dead = true;
+                match &mut object.kind {
+                    ObjectKind::ExplosiveBarrel { .. } | ObjectKind::FreezeBarrel { .. } => {
+                        object.dead = true;
+                    }
+                    ObjectKind::Turret { shots, charges, .. } => *charges = *shots,
+                    _ => {}
+                }
             }
         }
 
-        // Upgrades
+        self.particles_queue.push(SpawnParticles {
+            distribution: ParticleDistribution::Drawing {
+                points: drawing.points_smoothed.clone(),
+                width,
+            },
+            ..default()
+        })
+    }
+
+    /// Collect the closest upgrade the drawing went through.
+    pub fn collect_drawn_upgrades(&mut self, drawing: &Drawing, width: Coord) {
         let mut collected_idx = Vec::new();
         for (i, upgrade) in self.upgrades.iter().enumerate() {
+            if upgrade.price.map_or(false, |price| price > self.score) {
+                // Cannot afford
+                continue;
+            }
             let Some(delta) = delta_to_chain(upgrade.collider.position, &drawing.points_smoothed)
             else {
                 continue;
@@ -1034,95 +1315,137 @@ impl Model {
             }
         }
         if let Some((i, _)) = collected_idx.into_iter().min_by_key(|(_, d)| *d) {
-            let collected = self.upgrades.swap_remove(i);
-            self.collect_upgrade(collected);
-            self.upgrades.clear();
+            self.pick_upgrade(i);
         }
-
-        self.particles_queue.push(SpawnParticles {
-            distribution: ParticleDistribution::Drawing {
-                points: drawing.points_smoothed.clone(),
-                width,
-            },
-            ..default()
-        })
     }
 
     pub fn update_room_colliders(&mut self) {
-        struct Sides {
-            room: Aabb2<Coord>,
-            left: Vec<(Coord, Coord)>,
-            right: Vec<(Coord, Coord)>,
-            bottom: Vec<(Coord, Coord)>,
-            top: Vec<(Coord, Coord)>,
+        struct Wall {
+            direction: Direction,
+            line: Coord,
+            segments: Vec<(Coord, Coord)>,
         }
 
-        fn open(side: &mut Vec<(Coord, Coord)>, segment: (Coord, Coord)) {
-            for (i, (a, b)) in side.iter_mut().enumerate() {
-                let in0 = (*a..=*b).contains(&segment.0);
-                let in1 = (*a..=*b).contains(&segment.1);
-                if in0 {
-                    if in1 {
-                        let end = *b;
-                        *b = segment.0;
-                        side.insert(i + 1, (segment.1, end));
-                        break;
-                    } else {
-                        *b = segment.0;
-                    }
-                } else if in1 {
-                    *a = segment.1;
-                }
-            }
-            side.retain(|(a, b)| b > a);
+        fn open(segments: &mut Vec<(Coord, Coord)>, (from, to): (Coord, Coord)) {
+            *segments = segments
+                .iter()
+                .flat_map(|&(a, b)| [(a, b.min(from)), (a.max(to), b)])
+                .filter(|(a, b)| b > a)
+                .collect();
         }
 
-        let mut all_sides: BTreeMap<Index, Sides> = self
+        let mut all_walls: BTreeMap<Index, Vec<Wall>> = self
             .rooms
             .iter()
             .map(|(idx, room)| {
-                (
-                    idx,
-                    Sides {
-                        room: room.area,
-                        left: vec![(room.area.min.y, room.area.max.y)],
-                        right: vec![(room.area.min.y, room.area.max.y)],
-                        bottom: vec![(room.area.min.x, room.area.max.x)],
-                        top: vec![(room.area.min.x, room.area.max.x)],
-                    },
-                )
+                let parts = room.parts();
+                let walls = parts
+                    .iter()
+                    .flat_map(|part| {
+                        [
+                            (Direction::Left, part.min.x, (part.min.y, part.max.y)),
+                            (Direction::Right, part.max.x, (part.min.y, part.max.y)),
+                            (Direction::Down, part.min.y, (part.min.x, part.max.x)),
+                            (Direction::Up, part.max.y, (part.min.x, part.max.x)),
+                        ]
+                    })
+                    .map(|(direction, line, segment)| {
+                        // Remove the segments covered by the rest of the floor
+                        let mut segments = vec![segment];
+                        for other in &parts {
+                            let covered = match direction {
+                                Direction::Left => other.min.x < line && line <= other.max.x,
+                                Direction::Right => other.min.x <= line && line < other.max.x,
+                                Direction::Down => other.min.y < line && line <= other.max.y,
+                                Direction::Up => other.min.y <= line && line < other.max.y,
+                            };
+                            if covered {
+                                let span = match direction {
+                                    Direction::Left | Direction::Right => {
+                                        (other.min.y, other.max.y)
+                                    }
+                                    Direction::Down | Direction::Up => (other.min.x, other.max.x),
+                                };
+                                open(&mut segments, span);
+                            }
+                        }
+                        Wall {
+                            direction,
+                            line,
+                            segments,
+                        }
+                    })
+                    .collect();
+                (idx, walls)
             })
             .collect();
 
+        let mut doorways = Vec::new();
+        let mut add_doorway =
+            |rooms: (Index, Index), direction: Direction, line: Coord, (a, b): (Coord, Coord)| {
+                if b <= a {
+                    return;
+                }
+                let mid = (a + b) / r32(2.0);
+                let position = match direction {
+                    Direction::Left | Direction::Right => vec2(line, mid),
+                    Direction::Down | Direction::Up => vec2(mid, line),
+                };
+                doorways.push(Doorway {
+                    rooms,
+                    position,
+                    direction,
+                    width: b - a,
+                });
+            };
+
         for (idx, room) in &self.rooms {
-            if let Some((prev_idx, _)) = room.unlocked_after {
-                if let Some(prev_room) = self.rooms.get(prev_idx) {
-                    let room = room.area;
-                    let prev = prev_room.area;
-                    let sides = all_sides.get_mut(&prev_idx).unwrap();
-                    if prev.max.x == room.min.x {
-                        // Right side
-                        let intersection = (room.min.y.max(prev.min.y), room.max.y.min(prev.max.y));
-                        open(&mut sides.right, intersection);
-                        open(&mut all_sides.get_mut(&idx).unwrap().left, intersection);
-                    } else if prev.min.x == room.max.x {
-                        // Left side
-                        let intersection = (room.min.y.max(prev.min.y), room.max.y.min(prev.max.y));
-                        open(&mut sides.left, intersection);
-                        open(&mut all_sides.get_mut(&idx).unwrap().right, intersection);
-                    } else if prev.max.y == room.min.y {
-                        // Top side
-                        let intersection = (room.min.x.max(prev.min.x), room.max.x.min(prev.max.x));
-                        open(&mut sides.top, intersection);
-                        open(&mut all_sides.get_mut(&idx).unwrap().bottom, intersection);
-                    } else if prev.min.y == room.max.y {
-                        // Bottom side
-                        let intersection = (room.min.x.max(prev.min.x), room.max.x.min(prev.max.x));
-                        open(&mut sides.bottom, intersection);
-                        open(&mut all_sides.get_mut(&idx).unwrap().top, intersection);
-                    } else {
-                        unreachable!("invalid room setup")
+            let Some((prev_idx, _)) = room.unlocked_after else {
+                continue;
+            };
+            let Some(prev_room) = self.rooms.get(prev_idx) else {
+                continue;
+            };
+            let room = room.area;
+            let prev = prev_room.area;
+            let (direction, line) = if prev.max.x == room.min.x {
+                (Direction::Right, prev.max.x)
+            } else if prev.min.x == room.max.x {
+                (Direction::Left, prev.min.x)
+            } else if prev.max.y == room.min.y {
+                (Direction::Up, prev.max.y)
+            } else if prev.min.y == room.max.y {
+                (Direction::Down, prev.min.y)
+            } else {
+                unreachable!("invalid room setup")
+            };
+
+            // Open the walls where the floors of both rooms meet
+            let floor = |room_idx: Index, direction: Direction| -> Vec<(Coord, Coord)> {
+                all_walls[&room_idx]
+                    .iter()
+                    .filter(|wall| wall.direction == direction && wall.line == line)
+                    .flat_map(|wall| wall.segments.iter().copied())
+                    .collect()
+            };
+            let prev_floor = floor(prev_idx, direction);
+            let room_floor = floor(idx, direction.opposite());
+            for &(a, b) in &prev_floor {
+                for &(c, d) in &room_floor {
+                    let intersection = (a.max(c), b.min(d));
+                    if intersection.1 <= intersection.0 {
+                        continue;
+                    }
+                    for (room_idx, direction) in
+                        [(prev_idx, direction), (idx, direction.opposite())]
+                    {
+                        for wall in all_walls.get_mut(&room_idx).unwrap() {
+                            if wall.direction == direction && wall.line == line {
+                                open(&mut wall.segments, intersection);
+                            }
+                        }
                     }
+                    add_doorway((prev_idx, idx), direction, line, intersection);
                 }
             }
         }
@@ -1143,32 +1466,26 @@ impl Model {
             )
         };
 
-        let colliders =
-            all_sides
-                .into_iter()
-                .flat_map(|(idx, sides)| {
-                    sides
-                        .left
-                        .into_iter()
-                        .map(move |segment| (Direction::Left, wall_vert(sides.room.min.x, segment)))
-                        .chain(sides.right.into_iter().map(move |segment| {
-                            (Direction::Right, wall_vert(sides.room.max.x, segment))
-                        }))
-                        .chain(sides.bottom.into_iter().map(move |segment| {
-                            (Direction::Down, wall_horiz(sides.room.min.y, segment))
-                        }))
-                        .chain(sides.top.into_iter().map(move |segment| {
-                            (Direction::Up, wall_horiz(sides.room.max.y, segment))
-                        }))
-                        .map(move |(dir, collider)| (idx, dir, collider))
+        let colliders = all_walls
+            .into_iter()
+            .flat_map(|(idx, walls)| {
+                walls.into_iter().flat_map(move |wall| {
+                    wall.segments.into_iter().map(move |segment| {
+                        let collider = match wall.direction {
+                            Direction::Left | Direction::Right => wall_vert(wall.line, segment),
+                            Direction::Down | Direction::Up => wall_horiz(wall.line, segment),
+                        };
+                        (idx, wall.direction, collider)
+                    })
                 })
-                .collect();
+            })
+            .collect();
         self.room_colliders = colliders;
+        self.navigation = NavGraph { doorways };
     }
 
     pub fn unlock_room(&mut self, room_idx: Index, pos: Position) {
-        let mut rng = thread_rng();
-        let Some(room) = self.rooms.get_mut(room_idx) else {
+        let Some(room) = self.rooms.get(room_idx) else {
             return;
         };
 
@@ -1181,69 +1498,79 @@ impl Model {
         //     return;
         // }
 
-        room.expanded_direction = Some(closest);
-
-        let size = if let Some(boss) = self
-            .config
-            .bosses
-            .iter()
-            .find(|boss| boss.room == self.rooms_cleared + 1)
-        {
-            // Boss room
+        let Some(preview) = self.room_preview(room_idx, closest) else {
+            return;
+        };
+        if preview.kind == RoomKind::Boss {
             log::debug!("Generating boss room...");
-            boss.room_size
         } else {
-            // Normal room
             log::debug!("Generating next room...");
-            let mut gen = || {
-                (r32(rng.gen_range(15.0..=25.0))
-                    + self.config.difficulty.room_size_scaling * self.difficulty)
-                    .min(self.config.difficulty.room_size_max)
-            };
-            vec2(gen(), gen()).as_r32()
+        }
+        if self.in_starting_room() {
+            self.events.push(Event::RunStarted);
+        }
+        let Some(room) = self.rooms.get_mut(room_idx) else {
+            return;
         };
+        room.expanded_direction = Some(closest);
+        let new_room = room.neighbour_area(closest, preview.size);
 
-        let new_room = match closest {
-            Direction::Left => Aabb2::point(vec2(room.area.min.x, room.area.center().y))
-                .extend_left(size.x)
-                .extend_symmetric(vec2(Coord::ZERO, size.y) / r32(2.0)),
-            Direction::Right => Aabb2::point(vec2(room.area.max.x, room.area.center().y))
-                .extend_right(size.x)
-                .extend_symmetric(vec2(Coord::ZERO, size.y) / r32(2.0)),
-            Direction::Down => Aabb2::point(vec2(room.area.center().x, room.area.min.y))
-                .extend_down(size.y)
-                .extend_symmetric(vec2(size.x, Coord::ZERO) / r32(2.0)),
-            Direction::Up => Aabb2::point(vec2(room.area.center().x, room.area.max.y))
-                .extend_up(size.y)
-                .extend_symmetric(vec2(size.x, Coord::ZERO) / r32(2.0)),
-        };
         log::debug!("Expanding room near {room_idx:?}, {closest:?}");
+        self.last_unlocked = Some(closest);
+        let entrance = closest.opposite();
+        let previews = [
+            Direction::Left,
+            Direction::Right,
+            Direction::Down,
+            Direction::Up,
+        ]
+        .into_iter()
+        .filter(|&direction| direction != entrance)
+        .map(|direction| {
+            let kind = self.roll_room_kind(preview.kind);
+            (direction, self.roll_preview(kind))
+        })
+        .collect();
         let new_room = self.rooms.insert(Room {
             area: new_room,
-            unlocked_after: Some((room_idx, closest.opposite())),
+            shape: preview.shape,
+            floor: preview.shape.parts_in(new_room),
+            kind: preview.kind,
+            difficulty: preview.difficulty,
+            reward: preview.reward,
+            unlocked_after: Some((room_idx, entrance)),
             expanded_direction: None,
+            previews,
         });
         self.update_room_colliders();
-        self.spawn_enemies(new_room);
+        self.populate_room(new_room);
         self.events.push(Event::Sound(SoundEvent::Expand));
     }
 
-    pub fn spawn_enemies(&mut self, room_idx: Index) {
+    /// Spawn enemies worth the budget, telegraphing them first.
+    /// Returns the ids of the telegraphed enemies.
+    pub fn spawn_wave(&mut self, room_idx: Index, budget: R32) -> Vec<Id> {
         let Some(room) = self.rooms.get(room_idx) else {
-            return;
+            return Vec::new();
         };
 
-        let mut rng = thread_rng();
-        let mut difficulty = self.difficulty;
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let mut difficulty = budget;
+        // Groups of enemies that arrive together
+        let mut enemies: Vec<Vec<Enemy>> = Vec::new();
 
-        let find_position = |rng: &mut ThreadRng| -> Option<Position> {
-            let area = room.area.extend_uniform(-r32(3.0));
+        let find_position = |rng: &mut StdRng| -> Option<Position> {
             for _ in 0..50 {
-                let position = vec2(
-                    rng.gen_range(area.min.x..=area.max.x),
-                    rng.gen_range(area.min.y..=area.max.y),
-                );
-                if (self.player.body.collider.position - position).len() > r32(5.0) {
+                let Some(position) = room.random_position(r32(3.0), rng) else {
+                    continue;
+                };
+                let collider = Collider::new(position, Shape::circle(1.5));
+                if (self.player.body.collider.position - position).len() > r32(5.0)
+                    && self
+                        .obstacles
+                        .iter()
+                        .all(|obstacle| !obstacle.collider.check(&collider))
+                {
                     return Some(position);
                 }
             }
@@ -1251,24 +1578,24 @@ impl Model {
         };
         let mut spawn_enemy = |config: &EnemyConfig, position: Position| -> Enemy {
             let id = self.id_gen.gen();
-            Enemy::new(
-                id,
-                EnemyConfig {
-                    health: config.health
-                        + self.config.difficulty.enemy_health_scaling * self.difficulty,
-                    ..config.clone()
-                },
-                position,
-            )
+            let mut config = EnemyConfig {
+                health: config.health
+                    + self.config.difficulty.enemy_health_scaling * self.difficulty,
+                ..config.clone()
+            };
+            for modifier in &self.endless_modifiers {
+                modifier.apply(&mut config);
+            }
+            for mutator in &self.options.mutators {
+                mutator.apply_enemy(&mut config);
+            }
+            Enemy::new(id, config, position)
         };
 
-        if let Some(boss) = self
-            .config
-            .bosses
-            .iter()
-            .find(|boss| boss.room == self.rooms_cleared + 1)
-        {
+        if let Some((boss, endless_loop)) = self.room_boss() {
             // Boss room
+            let mut bosses = Vec::new();
+            let loop_scaling = r32(endless_loop as f32);
             for enemy in &boss.enemies {
                 let Some(enemy) = self.config.enemies.get(enemy) else {
                     log::error!("Enemy named {enemy:?} not found");
@@ -1279,11 +1606,29 @@ impl Model {
                     boss.is_boss = true;
                     // Boost boss max hp with player damage
                     let hp_boost = self.player.stats.whip.damage / r32(7.0) * r32(0.9);
-                    boss.health = Bounded::new_max(boss.health.max() * hp_boost);
-                    self.enemies.insert(boss);
+                    boss.health = Bounded::new_max(
+                        boss.health.max()
+                            * hp_boost
+                            * (R32::ONE + self.config.endless.health_scaling * loop_scaling),
+                    );
+                    boss.stats.damage *=
+                        R32::ONE + self.config.endless.damage_scaling * loop_scaling;
+                    // Until the intro keeps it invulnerable on the first update
+                    boss.invincibility.set_ratio(Time::ONE);
+                    bosses.push(boss);
                 }
             }
-            return;
+            let name = if endless_loop > 0 {
+                format!("{} {}", boss.name, roman_numeral(endless_loop + 1))
+            } else {
+                boss.name.clone()
+            };
+            self.boss_encounter = Some(BossEncounter {
+                name,
+                intro: Bounded::new_max(boss.intro),
+            });
+            self.telegraph_group(bosses);
+            return Vec::new();
         }
 
         while let Some(config) = self
@@ -1303,6 +1648,7 @@ impl Model {
                         match &config.shape {
                             Shape::Circle { radius } => {
                                 // Hexagon
+                                let mut group = Vec::new();
                                 let mut prev = None;
                                 for i in 0..6 {
                                     let angle = Angle::from_degrees(r32(360.0 * i as f32 / 6.0));
@@ -1313,8 +1659,9 @@ impl Model {
                                             Some((prev, enemy.body.collider.position - prev_pos));
                                     }
                                     prev = Some((enemy.id, enemy.body.collider.position));
-                                    self.enemies.insert(enemy);
+                                    group.push(enemy);
                                 }
+                                enemies.push(group);
                             }
                             Shape::Rectangle { .. } => {
                                 // 3x3 square
@@ -1328,6 +1675,7 @@ impl Model {
                                     (-1, -1),
                                     (-1, 0),
                                 ];
+                                let mut group = Vec::new();
                                 let mut prev = None;
                                 for (x, y) in poss {
                                     let position = position + vec2(x, y).as_r32();
@@ -1337,8 +1685,9 @@ impl Model {
                                             Some((prev, enemy.body.collider.position - prev_pos));
                                     }
                                     prev = Some((enemy.id, enemy.body.collider.position));
-                                    self.enemies.insert(enemy);
+                                    group.push(enemy);
                                 }
+                                enemies.push(group);
                             }
                             Shape::Triangle { .. } => {
                                 // TODO
@@ -1351,89 +1700,285 @@ impl Model {
 
             difficulty -= config.cost.unwrap_or(R32::ZERO);
             if let Some(position) = find_position(&mut rng) {
-                self.enemies.insert(spawn_enemy(config, position));
+                let mut enemy = spawn_enemy(config, position);
+                if self.difficulty >= self.config.elites.min_difficulty {
+                    let chance = self
+                        .endless_modifiers
+                        .iter()
+                        .map(|modifier| match modifier {
+                            EndlessModifier::EliteChance(chance) => *chance,
+                            _ => R32::ZERO,
+                        })
+                        .fold(self.config.elites.chance, R32::add);
+                    let modifiers = roll_elite(&self.config.elites, chance, difficulty, &mut rng);
+                    difficulty -= modifiers
+                        .iter()
+                        .map(|modifier| modifier.cost)
+                        .fold(R32::ZERO, R32::add);
+                    enemy.make_elite(modifiers);
+                }
+                enemies.push(vec![enemy]);
             }
         }
 
-        // Objects
-        let objects = [(
-            ObjectKind::ExplosiveBarrel {
-                range: self.player.stats.whip.width + r32(2.0),
-                damage: self.player.stats.whip.damage * r32(1.7),
-            },
-            0.4,
-        )];
-        for (kind, chance) in objects {
-            if !rng.gen_bool(chance) {
-                continue;
+        let ids = enemies.iter().flatten().map(|enemy| enemy.id).collect();
+        for group in enemies {
+            self.telegraph_group(group);
+        }
+        ids
+    }
+
+    /// Mark the spawn points of the enemies that arrive together.
+    pub fn telegraph_group(&mut self, enemies: Vec<Enemy>) {
+        let Some(group) = enemies.first().map(|enemy| enemy.id) else {
+            return;
+        };
+        let telegraph = self.config.waves.telegraph;
+        self.spawn_telegraphs
+            .extend(enemies.into_iter().map(|enemy| SpawnTelegraph {
+                enemy,
+                timer: Bounded::new_max(telegraph),
+                group,
+            }));
+    }
+
+    /// Roll the objects for a freshly unlocked room.
+    pub fn spawn_objects(&mut self, room_idx: Index) {
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let objects = self.config.objects.clone();
+        for spawn in &objects {
+            let count = match (self.options.mode, &spawn.object) {
+                (GameMode::Pacifist, ObjectConfig::ExplosiveBarrel { .. }) => {
+                    self.config.modes.pacifist_barrels
+                }
+                _ => usize::from(rng.gen_bool(spawn.chance)),
+            };
+            for _ in 0..count {
+                let kind = self.object_kind(&spawn.object);
+                if let ObjectConfig::Teleporter { .. } = spawn.object {
+                    self.spawn_teleporters(kind, room_idx);
+                } else {
+                    self.spawn_object(kind, room_idx);
+                }
             }
+        }
+    }
 
-            let area = room.area.extend_uniform(-r32(3.0));
-            for _ in 0..10 {
-                let position = vec2(
-                    rng.gen_range(area.min.x..=area.max.x),
-                    rng.gen_range(area.min.y..=area.max.y),
-                );
-                if self
-                    .enemies
+    pub fn explosive_barrel(&self) -> Option<ObjectKind> {
+        self.config
+            .objects
+            .iter()
+            .find(|spawn| matches!(spawn.object, ObjectConfig::ExplosiveBarrel { .. }))
+            .map(|spawn| self.object_kind(&spawn.object))
+    }
+
+    /// Find a spot in the room away from the enemies and obstacles.
+    fn object_position(&mut self, room_idx: Index) -> Option<Position> {
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let room = self.rooms.get(room_idx)?;
+        for _ in 0..10 {
+            let Some(position) = room.random_position(r32(3.0), &mut rng) else {
+                continue;
+            };
+            let collider = Collider::new(position, Shape::circle(1.5));
+            if self
+                .enemies
+                .iter()
+                .chain(
+                    self.spawn_telegraphs
+                        .iter()
+                        .map(|telegraph| &telegraph.enemy),
+                )
+                .all(|enemy| (enemy.body.collider.position - position).len() > r32(5.0))
+                && self
+                    .obstacles
                     .iter()
-                    .all(|enemy| (enemy.body.collider.position - position).len() > r32(5.0))
-                {
-                    self.objects.push(Object {
-                        dead: false,
-                        collider: Collider::new(position, Shape::square(r32(1.33))),
-                        kind,
-                    });
-                    break;
-                }
+                    .all(|obstacle| !obstacle.collider.check(&collider))
+            {
+                return Some(position);
+            }
+        }
+        None
+    }
+
+    /// Place an object in the room away from the enemies.
+    pub fn spawn_object(&mut self, kind: ObjectKind, room_idx: Index) -> bool {
+        let Some(position) = self.object_position(room_idx) else {
+            return false;
+        };
+        self.objects.push(Object {
+            dead: false,
+            fuse: None,
+            collider: Collider::new(position, kind.shape()),
+            kind,
+        });
+        true
+    }
+
+    /// Place a pair of teleporters leading into each other.
+    pub fn spawn_teleporters(&mut self, kind: ObjectKind, room_idx: Index) -> bool {
+        let Some(a) = self.object_position(room_idx) else {
+            return false;
+        };
+        let Some(b) = (0..5)
+            .filter_map(|_| self.object_position(room_idx))
+            .find(|b| (*b - a).len() > r32(8.0))
+        else {
+            return false;
+        };
+        for (position, to) in [(a, b), (b, a)] {
+            let mut kind = kind.clone();
+            if let ObjectKind::Teleporter { exit, .. } = &mut kind {
+                *exit = to;
             }
+            self.objects.push(Object {
+                dead: false,
+                fuse: None,
+                collider: Collider::new(position, kind.shape()),
+                kind,
+            });
+        }
+        true
+    }
+
+    /// Make sure pacifists always have a barrel to fight with.
+    pub fn restock_barrels(&mut self) {
+        if self.room_clear()
+            || self
+                .objects
+                .iter()
+                .any(|object| matches!(object.kind, ObjectKind::ExplosiveBarrel { .. }))
+        {
+            return;
+        }
+        let Some((room_idx, _)) = self
+            .rooms
+            .iter()
+            .find(|(_, room)| room.contains(self.player.body.collider.position))
+        else {
+            return;
+        };
+        if let Some(barrel) = self.explosive_barrel() {
+            self.spawn_object(barrel, room_idx);
         }
     }
 
     pub fn compress_rooms(&mut self, delta_time: Time) {
+        self.closing_walls.clear();
         if self.can_expand() {
             // Pause for expansion
             return;
         }
 
-        if self.rooms.len() == 1
-            && self
-                .config
-                .bosses
-                .iter()
-                .any(|boss| boss.room == self.rooms_cleared + 1)
-        {
+        if let GameMode::TimeAttack = self.options.mode {
+            // Only the clock is ticking
+            return;
+        }
+
+        let config = self.config.compression.clone();
+        if config.skip_boss_rooms && self.rooms.len() == 1 && self.room_boss().is_some() {
             // Dont compress the boss room
             return;
         }
 
-        let speed = r32(1.5) * r32(self.rooms.len() as f32).powf(r32(1.2));
+        self.compression_time += delta_time;
+        if let CompressionMode::Pulse { shrink, pause } = config.mode {
+            let period = (shrink + pause).as_f32().max(0.01);
+            if self.compression_time.as_f32() % period >= shrink.as_f32() {
+                // Pause between pulses
+                return;
+            }
+        }
+
+        let mut speed = config.speed * r32(self.rooms.len() as f32).powf(config.room_exponent);
+        if self.has_mutator(&Mutator::FastCompression) {
+            speed *= r32(2.0);
+        }
+        let shift = speed * delta_time;
         let ids: Vec<_> = self.rooms.iter().map(|(idx, _)| idx).collect();
-        for (_, room) in &mut self.rooms {
-            let dir = room
+        for (room_idx, room) in &mut self.rooms {
+            if room
                 .unlocked_after
-                .map_or(true, |(idx, _)| !ids.contains(&idx))
-                .then(|| {
-                    room.expanded_direction
-                        .or(room.unlocked_after.map(|(_, dir)| dir.opposite()))
-                })
-                .flatten();
-            if let Some(dir) = dir {
-                let shift = speed * delta_time;
+                .is_some_and(|(idx, _)| ids.contains(&idx))
+            {
+                // Only the oldest rooms are compressed
+                continue;
+            }
+
+            let Some(default) = room
+                .expanded_direction
+                .or(room.unlocked_after.map(|(_, dir)| dir.opposite()))
+            else {
+                // The room has not been left yet
+                continue;
+            };
+            // The wall shared with the next room has to stay in place
+            let keeps_doorway = |dir: &Direction| Some(dir.opposite()) != room.expanded_direction;
+            let (dirs, shift) = match config.mode {
+                CompressionMode::Linear | CompressionMode::Pulse { .. } => (vec![default], shift),
+                CompressionMode::LastUnlocked => (
+                    vec![self.last_unlocked.filter(keeps_doorway).unwrap_or(default)],
+                    shift,
+                ),
+                CompressionMode::Center => (
+                    [
+                        Direction::Left,
+                        Direction::Right,
+                        Direction::Down,
+                        Direction::Up,
+                    ]
+                    .into_iter()
+                    .filter(keeps_doorway)
+                    .collect(),
+                    shift / r32(2.0),
+                ),
+            };
+            for dir in dirs {
                 match dir {
                     Direction::Right => room.area.min.x += shift,
                     Direction::Left => room.area.max.x -= shift,
                     Direction::Up => room.area.min.y += shift,
                     Direction::Down => room.area.max.y -= shift,
                 }
+                self.closing_walls.push((room_idx, dir));
             }
+            room.floor = room.shape.parts_in(room.area);
         }
 
-        let min = r32(1.0);
+        // Warn about the rooms that are about to get squashed
+        let closing: Vec<_> = self.closing_walls.iter().map(|(idx, _)| *idx).collect();
+        let warnings: Vec<_> = self
+            .rooms
+            .iter()
+            .filter(|(idx, room)| {
+                closing.contains(idx)
+                    && room.area.width().min(room.area.height())
+                        <= config.min_size + config.warning_distance
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        if warnings
+            .iter()
+            .any(|idx| !self.squash_warnings.contains(idx))
+        {
+            self.events.push(Event::Sound(SoundEvent::SquashWarning));
+        }
+        self.squash_warnings = warnings;
+
+        // Obstacles get crushed by the walls
+        let rooms = &self.rooms;
+        self.obstacles.retain(|obstacle| {
+            rooms
+                .get(obstacle.room)
+                .map_or(false, |room| room.contains(obstacle.collider.position))
+        });
+
         let squashed: Vec<_> = self
             .rooms
             .iter()
-            .filter(|(_, room)| room.area.width() <= min || room.area.height() <= min)
+            .filter(|(_, room)| {
+                room.area.width() <= config.min_size || room.area.height() <= config.min_size
+            })
             .map(|(idx, _)| idx)
             .collect();
         if squashed.is_empty() {
@@ -1451,7 +1996,7 @@ impl Model {
         let should_squash = |pos| {
             self.rooms
                 .iter()
-                .find(|(_, room)| room.area.contains(pos))
+                .find(|(_, room)| room.contains(pos))
                 .map_or(true, |(idx, _)| ids.contains(&idx))
         };
 
@@ -1472,6 +2017,103 @@ impl Model {
     }
 }
 
+/// Apply the upgrade to the player's stats.
+/// Upgrades that change more than the stats are handled in [Model::collect_upgrade].
+fn upgrade_stats(stats: &mut PlayerConfig, effect: &UpgradeEffect) {
+    match effect {
+        UpgradeEffect::Width => {
+            stats.whip.width += r32(0.5);
+            stats.dash.width += r32(0.5);
+            stats.bow.width += r32(0.2);
+        }
+        UpgradeEffect::Range => {
+            stats.whip.max_distance += r32(3.0);
+            stats.dash.max_distance += r32(3.0);
+            stats.bow.max_distance += r32(3.0);
+        }
+        UpgradeEffect::Damage => {
+            stats.whip.damage += r32(3.0);
+            stats.dash.damage += r32(3.0);
+            stats.bow.damage += r32(3.0);
+        }
+        UpgradeEffect::Speed => {
+            stats.speed += r32(1.0);
+            stats.acceleration += r32(2.5);
+        }
+        UpgradeEffect::Health => {
+            stats.health += r32(10.0);
+        }
+        UpgradeEffect::Cooldown => {
+            for weapon in [Weapon::Whip, Weapon::Dash, Weapon::Bow, Weapon::FishingRod] {
+                let cooldown = &mut stats.weapon_mut(weapon).cooldown;
+                let max = cooldown.max() * r32(0.85);
+                *cooldown = Bounded::new(cooldown.value().min(max), Time::ZERO..=max);
+            }
+        }
+        UpgradeEffect::Ignite => {
+            add_effect(
+                &mut stats.whip.effects,
+                StatusEffectConfig {
+                    kind: StatusKind::Burn,
+                    duration: r32(2.0),
+                    magnitude: r32(2.0),
+                },
+            );
+        }
+        UpgradeEffect::Venom => {
+            add_effect(
+                &mut stats.bow.effects,
+                StatusEffectConfig {
+                    kind: StatusKind::Poison,
+                    duration: r32(4.0),
+                    magnitude: r32(1.0),
+                },
+            );
+        }
+        UpgradeEffect::Difficulty
+        | UpgradeEffect::Weapon(_)
+        | UpgradeEffect::Pierce
+        | UpgradeEffect::Trail
+        | UpgradeEffect::Echo => {}
+    }
+}
+
+/// Add a status effect to a weapon, or strengthen it if the weapon already has it.
+fn add_effect(effects: &mut Vec<StatusEffectConfig>, effect: StatusEffectConfig) {
+    match effects.iter_mut().find(|other| other.kind == effect.kind) {
+        Some(other) => {
+            other.duration += effect.duration / r32(2.0);
+            other.magnitude += effect.magnitude;
+        }
+        None => effects.push(effect),
+    }
+}
+
+fn roman_numeral(mut n: usize) -> String {
+    let mut result = String::new();
+    for (value, numeral) in [(10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I")] {
+        while n >= value {
+            result += numeral;
+            n -= value;
+        }
+    }
+    result
+}
+
+fn smooth_chain(points: impl IntoIterator<Item = Position>) -> Vec<Position> {
+    let points: Vec<_> = points
+        .into_iter()
+        .map(|pos| pos.as_f32())
+        .dedup_by(|a, b| (*a - *b).len_sqr() < 0.01)
+        .collect();
+    let chain = if points.len() < 3 {
+        Chain::new(points)
+    } else {
+        CardinalSpline::new(points, 0.5).chain(3)
+    };
+    chain.vertices.into_iter().map(|pos| pos.as_r32()).collect()
+}
+
 fn delta_to_chain(point: Position, chain: &[Position]) -> Option<vec2<Coord>> {
     // NOTE: potentially optimize by storing normal and distance separately
     let mut closest: Option<vec2<Coord>> = None;
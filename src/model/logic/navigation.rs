@@ -0,0 +1,64 @@
+use super::*;
+
+impl Model {
+    /// Returns the point an agent at `from` should steer towards in order to reach `target`.
+    /// If the target is not directly visible, the agent follows the room graph instead.
+    pub fn navigate(&self, from: Position, target: Position) -> Position {
+        if self.line_of_sight(from, target, RaycastFilter::WALLS) {
+            return target;
+        }
+
+        let find_room = |pos| {
+            self.rooms
+                .iter()
+                .find(|(_, room)| room.contains(pos))
+                .map(|(idx, _)| idx)
+        };
+        let (Some(from_room), Some(target_room)) = (find_room(from), find_room(target)) else {
+            return target;
+        };
+
+        self.navigation
+            .find_path(from_room, from, target_room, target)
+            .and_then(|path| path.first().copied())
+            .unwrap_or(target)
+    }
+
+    /// Steering correction that moves an agent sideways around the objects in front of it.
+    pub fn avoid_obstacles(
+        &self,
+        position: Position,
+        direction: vec2<Coord>,
+        radius: Coord,
+    ) -> vec2<Coord> {
+        let look_ahead = r32(3.0);
+        let mut avoidance = vec2::ZERO;
+        let colliders = self.objects.iter().map(|object| &object.collider).chain(
+            self.obstacles
+                .iter()
+                .filter(|obstacle| obstacle.kind.is_solid())
+                .map(|obstacle| &obstacle.collider),
+        );
+        for collider in colliders {
+            let delta = collider.position - position;
+            let along = vec2::dot(delta, direction);
+            if along <= Coord::ZERO || along > look_ahead {
+                continue;
+            }
+
+            let object_radius =
+                collider.compute_aabb().size().len() / r32(std::f32::consts::SQRT_2 * 2.0);
+            // Signed distance from the movement line, positive to the left
+            let side = direction.x * delta.y - direction.y * delta.x;
+            if side.abs() < radius + object_radius {
+                let sign = if side > Coord::ZERO {
+                    -Coord::ONE
+                } else {
+                    Coord::ONE
+                };
+                avoidance += direction.rotate_90() * sign * (Coord::ONE - along / look_ahead);
+            }
+        }
+        avoidance
+    }
+}
@@ -0,0 +1,184 @@
+use super::*;
+
+impl Model {
+    pub fn object_kind(&self, config: &ObjectConfig) -> ObjectKind {
+        match *config {
+            ObjectConfig::ExplosiveBarrel {
+                extra_range,
+                damage_multiplier,
+            } => ObjectKind::ExplosiveBarrel {
+                range: self.player.stats.whip.width + extra_range,
+                damage: self.player.stats.whip.damage * damage_multiplier,
+            },
+            ObjectConfig::FreezeBarrel { range, stun } => ObjectKind::FreezeBarrel { range, stun },
+            ObjectConfig::Turret {
+                range,
+                damage,
+                speed,
+                cooldown,
+                shots,
+            } => ObjectKind::Turret {
+                range,
+                damage,
+                speed,
+                cooldown: Bounded::new_zero(cooldown),
+                shots,
+                charges: 0,
+            },
+            ObjectConfig::BouncePad { strength } => ObjectKind::BouncePad { strength },
+            ObjectConfig::HealingFountain {
+                heal,
+                uses,
+                cooldown,
+            } => ObjectKind::HealingFountain {
+                heal,
+                uses,
+                cooldown: Bounded::new_zero(cooldown),
+            },
+            ObjectConfig::Teleporter { cooldown } => ObjectKind::Teleporter {
+                exit: vec2::ZERO,
+                cooldown: Bounded::new_zero(cooldown),
+            },
+            ObjectConfig::Crate => ObjectKind::Crate,
+        }
+    }
+
+    pub fn update_objects(&mut self, delta_time: Time) {
+        let mut heal = Hp::ZERO;
+        let mut teleport = None;
+        for object in &mut self.objects {
+            if let Some(fuse) = &mut object.fuse {
+                *fuse -= delta_time;
+                if *fuse <= Time::ZERO {
+                    object.dead = true;
+                }
+            }
+
+            let position = object.collider.position;
+            match &mut object.kind {
+                ObjectKind::Turret {
+                    range,
+                    damage,
+                    speed,
+                    cooldown,
+                    charges,
+                    ..
+                } => {
+                    cooldown.change(-delta_time);
+                    if *charges == 0 || cooldown.is_above_min() {
+                        continue;
+                    }
+
+                    // Shoot the closest enemy
+                    let Some(target) = self
+                        .enemies
+                        .iter()
+                        .filter(|enemy| !matches!(enemy.ai, EnemyAI::Bullet))
+                        .map(|enemy| enemy.body.collider.position)
+                        .filter(|target| (*target - position).len() < *range)
+                        .min_by_key(|target| (*target - position).len())
+                    else {
+                        continue;
+                    };
+                    cooldown.set_ratio(Time::ONE);
+                    *charges -= 1;
+
+                    let dir = (target - position).normalize_or_zero();
+                    let mut bullet = Minion {
+                        health: Bounded::new_max(r32(1.0)),
+                        // Spawn outside of the turret
+                        body: PhysicsBody::new(position + dir * r32(1.5), Shape::circle(0.3)),
+                        ai: MinionAI::Bullet {
+                            damage: *damage,
+                            explosion_damage: Hp::ZERO,
+                            explosion_radius: Coord::ZERO,
+                            effects: Vec::new(),
+                            explosion_effects: Vec::new(),
+                            pierce: false,
+                            hit: Vec::new(),
+                        },
+                    };
+                    bullet.body.velocity = dir * *speed;
+                    self.minions.push(bullet);
+                }
+                &mut ObjectKind::BouncePad { strength } => {
+                    let launch = |body: &mut PhysicsBody| {
+                        if !body.collider.check(&object.collider) {
+                            return false;
+                        }
+                        let dir = (body.collider.position - position).normalize_or_zero();
+                        body.velocity = dir * strength;
+                        true
+                    };
+                    launch(&mut self.player.body);
+                    for enemy in &mut self.enemies {
+                        if !enemy.is_boss && launch(&mut enemy.body) {
+                            enemy.last_knockback = self.game_time;
+                        }
+                    }
+                }
+                ObjectKind::HealingFountain {
+                    heal: amount,
+                    uses,
+                    cooldown,
+                } => {
+                    cooldown.change(-delta_time);
+                    if cooldown.is_min()
+                        && !self.player.health.is_max()
+                        && self.player.body.collider.check(&object.collider)
+                    {
+                        heal += *amount;
+                        cooldown.set_ratio(Time::ONE);
+                        *uses = uses.saturating_sub(1);
+                        if *uses == 0 {
+                            object.dead = true;
+                        }
+                    }
+                }
+                ObjectKind::Teleporter { exit, cooldown } => {
+                    cooldown.change(-delta_time);
+                    if cooldown.is_min() && self.player.body.collider.check(&object.collider) {
+                        teleport = Some((position, *exit));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some((entry, exit)) = teleport {
+            self.player.body.collider.position = exit;
+            // Dont teleport straight back
+            for object in &mut self.objects {
+                if let ObjectKind::Teleporter { cooldown, .. } = &mut object.kind {
+                    let position = object.collider.position;
+                    if position == entry || position == exit {
+                        cooldown.set_ratio(Time::ONE);
+                    }
+                }
+            }
+            for center in [entry, exit] {
+                self.particles_queue.push(SpawnParticles {
+                    kind: ParticleKind::Upgrade,
+                    distribution: ParticleDistribution::Circle {
+                        center,
+                        radius: r32(1.0),
+                    },
+                    ..default()
+                });
+            }
+            self.events.push(Event::Sound(SoundEvent::Expand));
+        }
+
+        if heal > Hp::ZERO {
+            self.heal_player(heal);
+            self.particles_queue.push(SpawnParticles {
+                kind: ParticleKind::Heal,
+                distribution: ParticleDistribution::Circle {
+                    center: self.player.body.collider.position,
+                    radius: r32(0.8),
+                },
+                ..default()
+            });
+        }
+    }
+}
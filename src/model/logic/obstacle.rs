@@ -0,0 +1,155 @@
+use super::*;
+
+impl Model {
+    /// Scatter obstacles around a freshly unlocked room.
+    pub fn spawn_obstacles(&mut self, room_idx: Index) {
+        if self.room_boss().is_some() {
+            // Keep the arena clear
+            return;
+        }
+        let Some(room) = self.rooms.get(room_idx) else {
+            return;
+        };
+
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let config = &self.config.obstacles;
+        let count = (room.area.width() * room.area.height() * config.density)
+            .as_f32()
+            .floor() as usize;
+        for _ in 0..count.min(config.max_count) {
+            let Ok(obstacle) = config
+                .table
+                .choose_weighted(&mut rng, |config| config.weight.as_f32())
+            else {
+                break;
+            };
+            let size = if rng.gen_bool(0.5) {
+                obstacle.size
+            } else {
+                vec2(obstacle.size.y, obstacle.size.x)
+            };
+            let shape = if obstacle.round {
+                Shape::circle(size.x / r32(2.0))
+            } else {
+                Shape::rectangle(size.x, size.y)
+            };
+
+            // Keep the doorways and the player clear
+            let margin = size.x.max(size.y) / r32(2.0) + r32(4.0);
+            for _ in 0..10 {
+                let Some(position) = room.random_position(margin, &mut rng) else {
+                    continue;
+                };
+                let free = (self.player.body.collider.position - position).len() > margin
+                    && self
+                        .obstacles
+                        .iter()
+                        .all(|other| (other.collider.position - position).len() > margin);
+                if free {
+                    self.obstacles.push(Obstacle {
+                        room: room_idx,
+                        collider: Collider::new(position, shape),
+                        kind: obstacle.kind,
+                    });
+                    break;
+                }
+            }
+        }
+    }
+
+    pub fn obstacle_collisions(&mut self, delta_time: Time) {
+        for obstacle in &self.obstacles {
+            // Player
+            let player = &mut self.player;
+            if obstacle.kind.is_solid() {
+                if let Some(collision) = player.body.collider.collide(&obstacle.collider) {
+                    push_out(&mut player.body, collision);
+                }
+            } else if player.body.collider.check(&obstacle.collider) {
+                match obstacle.kind {
+                    ObstacleKind::Spikes { damage } => {
+                        if player.invincibility.is_min() {
+                            player.health.change(-damage * delta_time);
+                            player.last_hit = self.game_time;
+                        }
+                    }
+                    ObstacleKind::Mud { slow } => player.status.apply(&mud_slow(slow)),
+                    _ => {}
+                }
+            }
+
+            // Player shots
+            if obstacle.kind.blocks_shots() {
+                for minion in &mut self.minions {
+                    if minion.body.collider.check(&obstacle.collider) {
+                        minion.health.set_ratio(Hp::ZERO);
+                    }
+                }
+            }
+
+            // Enemies
+            for enemy in &mut self.enemies {
+                if let EnemyAI::Bullet = enemy.ai {
+                    if obstacle.kind.blocks_shots() && enemy.body.collider.check(&obstacle.collider)
+                    {
+                        enemy.health.set_ratio(Hp::ZERO);
+                    }
+                    continue;
+                }
+
+                let knocked = self.game_time - enemy.last_knockback < r32(1.0);
+                match obstacle.kind {
+                    ObstacleKind::Pit if knocked && !enemy.is_boss => {
+                        // Fall in once the center is over the pit
+                        let center =
+                            Collider::new(enemy.body.collider.position, Shape::circle(0.1));
+                        if center.check(&obstacle.collider) && enemy.health.is_above_min() {
+                            enemy.health.set_ratio(Hp::ZERO);
+                            self.particles_queue.push(SpawnParticles {
+                                kind: ParticleKind::Damage,
+                                distribution: ParticleDistribution::Circle {
+                                    center: enemy.body.collider.position,
+                                    radius: r32(0.5),
+                                },
+                                ..default()
+                            });
+                        }
+                    }
+                    kind if kind.is_solid() => {
+                        if let Some(collision) = enemy.body.collider.collide(&obstacle.collider) {
+                            push_out(&mut enemy.body, collision);
+                        }
+                    }
+                    ObstacleKind::Spikes { damage } => {
+                        if enemy.body.collider.check(&obstacle.collider) {
+                            enemy.take_damage(damage * delta_time);
+                        }
+                    }
+                    ObstacleKind::Mud { slow } => {
+                        if enemy.body.collider.check(&obstacle.collider) {
+                            enemy.status.apply(&mud_slow(slow));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn mud_slow(slow: R32) -> StatusEffectConfig {
+    StatusEffectConfig {
+        kind: StatusKind::Slow,
+        duration: r32(0.2),
+        magnitude: slow,
+    }
+}
+
+/// Move the body out of the collision and stop it from moving further in.
+fn push_out(body: &mut PhysicsBody, collision: Collision) {
+    body.collider.position -= collision.normal * collision.penetration;
+    let projection = vec2::dot(body.velocity, collision.normal);
+    if projection > Coord::ZERO {
+        body.velocity -= collision.normal * projection;
+    }
+}
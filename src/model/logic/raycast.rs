@@ -0,0 +1,59 @@
+use super::*;
+
+impl Model {
+    /// Find the closest thing the ray hits within `max_distance`.
+    pub fn raycast(
+        &self,
+        origin: Position,
+        direction: vec2<Coord>,
+        max_distance: Coord,
+        filter: RaycastFilter,
+    ) -> Option<RaycastHit> {
+        let direction = direction.normalize_or_zero();
+        if direction == vec2::ZERO {
+            return None;
+        }
+
+        let walls = self
+            .room_colliders
+            .iter()
+            .filter(|_| filter.walls)
+            .map(|(idx, _, collider)| (RaycastTarget::Wall(*idx), collider));
+        let objects = self
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|_| filter.objects)
+            .map(|(i, object)| (RaycastTarget::Object(i), &object.collider));
+        let obstacles = self
+            .obstacles
+            .iter()
+            .enumerate()
+            .filter(|(_, obstacle)| filter.obstacles && obstacle.kind.blocks_shots())
+            .map(|(i, obstacle)| (RaycastTarget::Obstacle(i), &obstacle.collider));
+
+        walls
+            .chain(objects)
+            .chain(obstacles)
+            .filter_map(|(target, collider)| {
+                collider
+                    .raycast(origin, direction, max_distance)
+                    .map(|distance| RaycastHit {
+                        distance,
+                        point: origin + direction * distance,
+                        target,
+                    })
+            })
+            .min_by_key(|hit| hit.distance)
+    }
+
+    /// Check whether nothing that passes the filter is in the way between the two points.
+    pub fn line_of_sight(&self, from: Position, to: Position, filter: RaycastFilter) -> bool {
+        let delta = to - from;
+        let distance = delta.len();
+        if distance.approx_eq(&Coord::ZERO) {
+            return true;
+        }
+        self.raycast(from, delta, distance, filter).is_none()
+    }
+}
@@ -0,0 +1,254 @@
+use super::*;
+
+impl Model {
+    /// Pick the kind of the room unlocked after a room of the given kind.
+    pub fn roll_room_kind(&mut self, previous: RoomKind) -> RoomKind {
+        if previous.is_special() {
+            return RoomKind::Combat;
+        }
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        self.config
+            .room_kinds
+            .table
+            .choose_weighted(&mut rng, |config| config.weight.as_f32())
+            .map_or(RoomKind::Combat, |config| config.kind)
+    }
+
+    /// Roll the room waiting behind a side.
+    pub fn roll_preview(&mut self, kind: RoomKind) -> RoomPreview {
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let mut gen = || {
+            (r32(rng.gen_range(15.0..=25.0))
+                + self.config.difficulty.room_size_scaling * self.difficulty)
+                .min(self.config.difficulty.room_size_max)
+        };
+        let size = vec2(gen(), gen()).as_r32();
+
+        let mut shape = self
+            .config
+            .room_shapes
+            .choose_weighted(&mut rng, |config| config.weight.as_f32())
+            .map_or(RoomShape::Rectangle, |config| config.shape);
+        if let RoomShape::LShape { flip_x, flip_y } = &mut shape {
+            *flip_x = rng.gen();
+            *flip_y = rng.gen();
+        }
+
+        let config = &self.config.routes;
+        let difficulty = match kind {
+            RoomKind::Combat | RoomKind::Challenge => {
+                r32(rng.gen_range(config.min_difficulty.as_f32()..=config.max_difficulty.as_f32()))
+            }
+            _ => R32::ONE,
+        };
+        let reward = match kind {
+            RoomKind::Rest => None,
+            _ => self.roll_upgrades(self.upgrade_pool(), 1, &mut rng).pop(),
+        };
+
+        RoomPreview {
+            size,
+            shape,
+            kind,
+            difficulty,
+            reward,
+        }
+    }
+
+    /// The breakable wall the player is aiming at with the cursor or the drawing.
+    pub fn targeted_wall(&self) -> Option<(Index, Direction)> {
+        let target = self
+            .player
+            .draw_action
+            .as_ref()
+            .and_then(|drawing| drawing.points_smoothed.last().copied())
+            .unwrap_or(self.cursor_pos);
+        let (room_idx, room) = self
+            .rooms
+            .iter()
+            .find(|(_, room)| room.contains(self.player.body.collider.position))?;
+
+        let area = room.area;
+        let direction = if area.contains(target) {
+            // Approaching the wall from the inside
+            let (distance, direction) = [
+                (target.x - area.min.x, Direction::Left),
+                (area.max.x - target.x, Direction::Right),
+                (target.y - area.min.y, Direction::Down),
+                (area.max.y - target.y, Direction::Up),
+            ]
+            .into_iter()
+            .min_by_key(|(distance, _)| *distance)?;
+            if distance > r32(3.0) {
+                return None;
+            }
+            direction
+        } else {
+            room.closest_wall(target).1
+        };
+        self.is_breakable_wall(room_idx, direction)
+            .then_some((room_idx, direction))
+    }
+
+    /// Fill a freshly unlocked room according to its kind.
+    pub fn populate_room(&mut self, room_idx: Index) {
+        let Some(room) = self.rooms.get(room_idx) else {
+            return;
+        };
+        let area = room.area;
+        let kind = room.kind;
+        let reward = room.reward.clone();
+        let difficulty = self.difficulty * room.difficulty;
+        let config = self.config.room_kinds.clone();
+        match kind {
+            RoomKind::Combat => {
+                self.spawn_obstacles(room_idx);
+                self.spawn_enemies(room_idx);
+                self.spawn_objects(room_idx);
+            }
+            RoomKind::Boss => {
+                self.spawn_enemies(room_idx);
+            }
+            RoomKind::Treasure => {
+                self.rerolls += self.config.upgrade_choices.treasure_rerolls;
+                self.spawn_upgrades(area, self.config.upgrades_per_level, None, reward);
+            }
+            RoomKind::Shop => {
+                let price =
+                    config.shop_price + config.shop_price_scaling * self.rooms_cleared as Score;
+                self.spawn_upgrades(area, config.shop_upgrades, Some(price), reward);
+            }
+            RoomKind::Challenge => {
+                self.spawn_obstacles(room_idx);
+                self.spawn_enemies(room_idx);
+                self.spawn_objects(room_idx);
+                self.challenge = Some(Challenge {
+                    room: room_idx,
+                    difficulty,
+                    timer: Bounded::new_max(config.challenge_duration),
+                    next_wave: Bounded::new_max(config.challenge_wave_delay),
+                });
+            }
+            RoomKind::Rest => {
+                let kind = self.object_kind(&config.rest_fountain);
+                self.objects.push(Object {
+                    dead: false,
+                    fuse: None,
+                    collider: Collider::new(area.center(), kind.shape()),
+                    kind,
+                });
+            }
+        }
+    }
+
+    /// Upgrades that can currently be offered.
+    pub fn upgrade_pool(&self) -> Vec<UpgradeEffect> {
+        let mut options = vec![
+            UpgradeEffect::Width,
+            UpgradeEffect::Range,
+            UpgradeEffect::Damage,
+            UpgradeEffect::Speed,
+            UpgradeEffect::Difficulty,
+            UpgradeEffect::Ignite,
+            UpgradeEffect::Venom,
+            UpgradeEffect::Pierce,
+            UpgradeEffect::Trail,
+            UpgradeEffect::Echo,
+        ];
+        options.extend(
+            [Weapon::Whip, Weapon::Dash, Weapon::Bow, Weapon::FishingRod]
+                .into_iter()
+                .filter(|weapon| *weapon != self.player.active_weapon)
+                .map(UpgradeEffect::Weapon),
+        );
+        // Upgrades bought between runs
+        options.extend(
+            self.options
+                .unlocks
+                .iter()
+                .filter_map(|unlock| match unlock {
+                    Unlock::Upgrade(effect) => Some(effect.clone()),
+                    _ => None,
+                }),
+        );
+        if self.has_mutator(&Mutator::WeaponsOnly) {
+            options.retain(|effect| matches!(effect, UpgradeEffect::Weapon(_)));
+        }
+        options.retain(|effect| {
+            !self.banished.contains(effect)
+                && !(effect.rarity() == Rarity::Legendary && self.player.has_upgrade(effect))
+        });
+        options
+    }
+
+    /// Lay out a row of random upgrades in the middle of the area.
+    /// The reward is always among them, if it can be offered.
+    pub fn spawn_upgrades(
+        &mut self,
+        area: Aabb2<Coord>,
+        count: usize,
+        price: Option<Score>,
+        reward: Option<UpgradeEffect>,
+    ) {
+        let offset = if area.size().aspect() > r32(0.5) {
+            vec2(2.5, 0.0).as_r32()
+        } else {
+            vec2(0.0, 2.5).as_r32()
+        };
+
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let mut pool = self.upgrade_pool();
+        let mut options = Vec::new();
+        if let Some(reward) = reward.filter(|reward| pool.contains(reward)) {
+            pool.retain(|effect| *effect != reward);
+            options.push(reward);
+        }
+        let rest = count.saturating_sub(options.len());
+        options.extend(self.roll_upgrades(pool, rest, &mut rng));
+        options.shuffle(&mut rng);
+        let upgrades = options.iter().enumerate().map(|(i, effect)| Upgrade {
+            collider: Collider::new(
+                area.center() + offset * r32(i as f32 - (options.len() as f32 - 1.0) / 2.0),
+                Shape::circle(0.5),
+            ),
+            effect: effect.clone(),
+            price,
+        });
+        self.upgrades.extend(upgrades);
+    }
+
+    pub fn update_challenge(&mut self, delta_time: Time) {
+        let Some(challenge) = &mut self.challenge else {
+            return;
+        };
+        if !self.rooms.contains(challenge.room) {
+            self.challenge = None;
+            return;
+        }
+
+        challenge.timer.change(-delta_time);
+        if challenge.timer.is_min() {
+            // Survived
+            self.challenge = None;
+            self.waves = None;
+            self.spawn_telegraphs.clear();
+            if self.room_clear() {
+                self.finish_battle();
+            } else {
+                // The battle finishes once the remaining enemies die
+                for enemy in &mut self.enemies {
+                    enemy.health.set_ratio(Hp::ZERO);
+                }
+            }
+            return;
+        }
+
+        challenge.next_wave.change(-delta_time);
+        if challenge.next_wave.is_min() || self.room_clear() {
+            challenge.next_wave.set_ratio(Time::ONE);
+            let room = challenge.room;
+            let difficulty = challenge.difficulty;
+            self.spawn_wave(room, difficulty);
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use super::*;
+
+impl Model {
+    /// Relative chance of the upgrade being offered at the current difficulty.
+    pub fn upgrade_weight(&self, effect: &UpgradeEffect) -> R32 {
+        let rarity = effect.rarity();
+        self.config
+            .rarities
+            .iter()
+            .find(|config| config.rarity == rarity)
+            .map_or(R32::ZERO, |config| {
+                config.weight + config.difficulty_scaling * self.difficulty
+            })
+    }
+
+    /// Pick distinct upgrades from the pool, weighted by their rarity.
+    pub fn roll_upgrades(
+        &self,
+        mut pool: Vec<UpgradeEffect>,
+        count: usize,
+        rng: &mut impl Rng,
+    ) -> Vec<UpgradeEffect> {
+        let mut options = Vec::new();
+        for _ in 0..count {
+            let Ok(i) = (0..pool.len())
+                .collect::<Vec<_>>()
+                .choose_weighted(rng, |&i| self.upgrade_weight(&pool[i]).as_f32())
+                .copied()
+            else {
+                break;
+            };
+            options.push(pool.swap_remove(i));
+        }
+        options
+    }
+
+    /// Indices of the free upgrades, only one of them can be taken.
+    pub fn upgrade_choices(&self) -> Vec<usize> {
+        self.upgrades
+            .iter()
+            .enumerate()
+            .filter(|(_, upgrade)| upgrade.price.is_none())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The upgrade under the cursor.
+    pub fn hovered_upgrade(&self) -> Option<usize> {
+        self.upgrades
+            .iter()
+            .position(|upgrade| (upgrade.collider.position - self.cursor_pos).len() < r32(1.0))
+    }
+
+    /// Take the upgrade, paying for it in the shop.
+    pub fn pick_upgrade(&mut self, index: usize) {
+        if index >= self.upgrades.len() {
+            return;
+        }
+        let upgrade = self.upgrades.swap_remove(index);
+        if let Some(price) = upgrade.price {
+            self.score -= price;
+        } else {
+            // Free upgrades are a choice of one
+            self.upgrades.retain(|upgrade| upgrade.price.is_some());
+        }
+        self.collect_upgrade(upgrade);
+    }
+
+    /// Take the n-th free upgrade.
+    pub fn choose_upgrade(&mut self, choice: usize) {
+        if let Some(&index) = self.upgrade_choices().get(choice) {
+            self.pick_upgrade(index);
+        }
+    }
+
+    /// Replace all free upgrades with new ones.
+    pub fn reroll_upgrades(&mut self) -> bool {
+        let choices = self.upgrade_choices();
+        if self.rerolls == 0 || choices.is_empty() {
+            return false;
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let current: Vec<UpgradeEffect> = choices
+            .iter()
+            .map(|&i| self.upgrades[i].effect.clone())
+            .collect();
+        let pool: Vec<UpgradeEffect> = self
+            .upgrade_pool()
+            .into_iter()
+            .filter(|effect| !current.contains(effect))
+            .collect();
+        if pool.is_empty() {
+            return false;
+        }
+        let mut options = self.roll_upgrades(pool, choices.len(), &mut rng);
+        for &i in &choices {
+            // Keep the old ones if the pool runs out
+            if let Some(effect) = options.pop() {
+                self.upgrades[i].effect = effect;
+            }
+            self.upgrade_particles(i);
+        }
+        self.rerolls -= 1;
+        true
+    }
+
+    /// Never offer the upgrade again this run, and replace it with another.
+    pub fn banish_upgrade(&mut self, index: usize) -> bool {
+        if self.banishes == 0 {
+            return false;
+        }
+        let Some(upgrade) = self.upgrades.get(index) else {
+            return false;
+        };
+        if upgrade.price.is_some() {
+            return false;
+        }
+
+        self.banished.push(upgrade.effect.clone());
+        self.banishes -= 1;
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let current: Vec<UpgradeEffect> = self
+            .upgrade_choices()
+            .into_iter()
+            .map(|i| self.upgrades[i].effect.clone())
+            .collect();
+        let pool = self
+            .upgrade_pool()
+            .into_iter()
+            .filter(|effect| !current.contains(effect))
+            .collect();
+        let replacement = self.roll_upgrades(pool, 1, &mut rng).pop();
+        self.upgrade_particles(index);
+        match replacement {
+            Some(effect) => self.upgrades[index].effect = effect,
+            None => {
+                self.upgrades.swap_remove(index);
+            }
+        }
+        true
+    }
+
+    /// Activate the synergies of the held upgrades.
+    pub fn update_synergies(&mut self) {
+        let player = &mut self.player;
+        for synergy in Synergy::ALL {
+            if player.has_synergy(synergy)
+                || !synergy
+                    .requires()
+                    .iter()
+                    .all(|required| player.has_upgrade(required))
+            {
+                continue;
+            }
+            log::debug!("Synergy {synergy:?} activated");
+            player.synergies.push(synergy);
+            self.particles_queue.push(SpawnParticles {
+                kind: ParticleKind::Upgrade,
+                distribution: ParticleDistribution::Circle {
+                    center: player.body.collider.position,
+                    radius: r32(1.5),
+                },
+                density: r32(2.0),
+                ..default()
+            });
+        }
+    }
+
+    /// Repeat the whip strokes and hurt the enemies on the dash trails.
+    pub fn update_legendaries(&mut self, delta_time: Time) {
+        let mut ready = Vec::new();
+        self.echoes.retain_mut(|echo| {
+            echo.delay -= delta_time;
+            if echo.delay > Time::ZERO {
+                return true;
+            }
+            ready.push(echo.clone());
+            false
+        });
+        for echo in ready {
+            let drawing = Drawing {
+                points_raw: Vec::new(),
+                points_smoothed: echo.points,
+            };
+            self.damage_around(drawing, echo.width, echo.damage, &echo.effects);
+        }
+
+        for trail in &mut self.trails {
+            trail.lifetime.change(-delta_time);
+            for enemy in &mut self.enemies {
+                if enemy.invincibility.is_above_min() {
+                    continue;
+                }
+                let enemy_radius = enemy.body.collider.compute_aabb().size().len()
+                    / r32(std::f32::consts::SQRT_2 * 2.0);
+                let hit = delta_to_chain(enemy.body.collider.position, &trail.points)
+                    .map_or(false, |delta| delta.len() < trail.width + enemy_radius);
+                if !hit {
+                    continue;
+                }
+                enemy.take_damage(trail.damage * delta_time);
+                if !trail.affected.contains(&enemy.id) {
+                    trail.affected.push(enemy.id);
+                    for effect in &trail.effects {
+                        enemy.status.apply(effect);
+                    }
+                }
+            }
+        }
+        self.trails.retain(|trail| trail.lifetime.is_above_min());
+    }
+
+    fn upgrade_particles(&mut self, index: usize) {
+        let Some(upgrade) = self.upgrades.get(index) else {
+            return;
+        };
+        self.particles_queue.push(SpawnParticles {
+            kind: ParticleKind::Upgrade,
+            distribution: ParticleDistribution::Circle {
+                center: upgrade.collider.position,
+                radius: r32(0.8),
+            },
+            ..default()
+        });
+    }
+
+    /// Exact changes made by collecting the upgrade.
+    pub fn upgrade_deltas(&self, effect: &UpgradeEffect) -> Vec<String> {
+        let mut deltas = match effect {
+            UpgradeEffect::Difficulty => vec![
+                format!(
+                    "DIFFICULTY {:+.1}",
+                    self.config.difficulty.upgrade_amount.as_f32()
+                ),
+                format!(
+                    "SCORE x{:+.1}",
+                    self.config.score.upgrade_multiplier.as_f32()
+                ),
+            ],
+            UpgradeEffect::Weapon(weapon) => {
+                vec![format!("WEAPON: {:?}", weapon).to_uppercase()]
+            }
+            UpgradeEffect::Pierce => vec!["ARROWS PIERCE".to_string()],
+            UpgradeEffect::Trail => vec!["DASH LEAVES A TRAIL".to_string()],
+            UpgradeEffect::Echo => vec!["WHIP STROKES ECHO".to_string()],
+            _ => self.stat_deltas(effect),
+        };
+
+        // Synergies completed by the upgrade
+        deltas.extend(
+            Synergy::ALL
+                .into_iter()
+                .filter(|&synergy| {
+                    !self.player.has_synergy(synergy)
+                        && synergy
+                            .requires()
+                            .iter()
+                            .all(|required| required == effect || self.player.has_upgrade(required))
+                })
+                .map(|synergy| format!("+{}: {}", synergy.name(), synergy.description())),
+        );
+        deltas
+    }
+
+    fn stat_deltas(&self, effect: &UpgradeEffect) -> Vec<String> {
+        let before = &self.player.stats;
+        let mut after = before.clone();
+        upgrade_stats(&mut after, effect);
+
+        let mut deltas = Vec::new();
+        let mut delta = |name: &str, before: R32, after: R32| {
+            if after != before {
+                deltas.push(format!("{} {:+.1}", name, (after - before).as_f32()));
+            }
+        };
+        delta("HEALTH", before.health, after.health);
+        delta("SPEED", before.speed, after.speed);
+        delta("ACCELERATION", before.acceleration, after.acceleration);
+        for (name, weapon) in [
+            ("WHIP", Weapon::Whip),
+            ("DASH", Weapon::Dash),
+            ("BOW", Weapon::Bow),
+        ] {
+            let before = before.weapon(weapon);
+            let after = after.weapon(weapon);
+            delta(&format!("{name} WIDTH"), before.width, after.width);
+            delta(
+                &format!("{name} RANGE"),
+                before.max_distance,
+                after.max_distance,
+            );
+            delta(&format!("{name} DAMAGE"), before.damage, after.damage);
+            delta(
+                &format!("{name} COOLDOWN"),
+                before.cooldown.max(),
+                after.cooldown.max(),
+            );
+            for effect in &after.effects {
+                let old = before
+                    .effects
+                    .iter()
+                    .find(|other| other.kind == effect.kind);
+                let kind = format!("{:?}", effect.kind).to_uppercase();
+                delta(
+                    &format!("{name} {kind}"),
+                    old.map_or(R32::ZERO, |old| old.magnitude),
+                    effect.magnitude,
+                );
+                delta(
+                    &format!("{name} {kind} TIME"),
+                    old.map_or(R32::ZERO, |old| old.duration),
+                    effect.duration,
+                );
+            }
+        }
+        deltas
+    }
+}
@@ -0,0 +1,154 @@
+use super::*;
+
+impl Model {
+    /// Fill a freshly unlocked room with enemies, split into waves.
+    pub fn spawn_enemies(&mut self, room_idx: Index) {
+        if self.room_boss().is_some() {
+            // Bosses arrive all at once
+            self.spawn_wave(room_idx, self.difficulty);
+            return;
+        }
+
+        let Some(room) = self.rooms.get(room_idx) else {
+            return;
+        };
+        let difficulty = self.difficulty * room.difficulty;
+        let area = room.area.width() * room.area.height();
+        let fits = |layout: &&WaveLayoutConfig| layout.min_area.map_or(true, |min| area >= min);
+        let layouts = &self.config.waves.layouts;
+        let mut options: Vec<&WaveLayoutConfig> = layouts
+            .iter()
+            .filter(fits)
+            .filter(|layout| layout.kinds.contains(&room.kind))
+            .collect();
+        if options.is_empty() {
+            options = layouts
+                .iter()
+                .filter(fits)
+                .filter(|layout| layout.kinds.is_empty())
+                .collect();
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.rng.gen());
+        let (fractions, trigger) = options
+            .choose_weighted(&mut rng, |layout| layout.weight.as_f32())
+            .map_or((vec![R32::ONE], WaveTrigger::Alive(R32::ZERO)), |layout| {
+                (layout.waves.clone(), layout.trigger)
+            });
+        self.waves = Some(RoomWaves {
+            room: room_idx,
+            trigger,
+            budgets: fractions
+                .into_iter()
+                .map(|fraction| fraction * difficulty)
+                .collect(),
+            current: Vec::new(),
+            time: Time::ZERO,
+        });
+        self.next_wave();
+    }
+
+    fn next_wave(&mut self) {
+        let Some(waves) = &mut self.waves else {
+            return;
+        };
+        if waves.budgets.is_empty() {
+            self.waves = None;
+            return;
+        }
+        let budget = waves.budgets.remove(0);
+        let room = waves.room;
+
+        let ids = self.spawn_wave(room, budget);
+        if let Some(waves) = &mut self.waves {
+            if waves.budgets.is_empty() {
+                // Last wave
+                self.waves = None;
+            } else {
+                waves.current = ids;
+                waves.time = Time::ZERO;
+            }
+        }
+    }
+
+    pub fn update_waves(&mut self, delta_time: Time) {
+        // Telegraphs
+        let player = &self.player.body.collider;
+        let distance = self.config.waves.player_distance;
+        for telegraph in &mut self.spawn_telegraphs {
+            telegraph.timer.change(-delta_time);
+        }
+        // A group waits for all of its members
+        let held: Vec<Id> = self
+            .spawn_telegraphs
+            .iter()
+            .filter(|telegraph| {
+                let position = telegraph.enemy.body.collider.position;
+                telegraph.timer.is_above_min() || (player.position - position).len() < distance
+            })
+            .map(|telegraph| telegraph.group)
+            .collect();
+        for telegraph in std::mem::take(&mut self.spawn_telegraphs) {
+            if !held.contains(&telegraph.group) {
+                self.spawn_queue.push(telegraph.enemy);
+                continue;
+            }
+            let collider = &telegraph.enemy.body.collider;
+            self.particles_queue.push(SpawnParticles {
+                kind: ParticleKind::Telegraph,
+                density: r32(30.0) * delta_time,
+                distribution: ParticleDistribution::Circle {
+                    center: collider.position,
+                    radius: collider.compute_aabb().size().len() / r32(2.0),
+                },
+                lifetime: r32(0.3)..=r32(0.6),
+                ..default()
+            });
+            self.spawn_telegraphs.push(telegraph);
+        }
+
+        // Next wave
+        let Some(waves) = &mut self.waves else {
+            return;
+        };
+        if !self.rooms.contains(waves.room) {
+            self.waves = None;
+            return;
+        }
+        waves.time += delta_time;
+        let alive = waves
+            .current
+            .iter()
+            .filter(|&&id| {
+                self.enemies.get(&id).is_some()
+                    || self.spawn_queue.iter().any(|enemy| enemy.id == id)
+                    || self
+                        .spawn_telegraphs
+                        .iter()
+                        .any(|telegraph| telegraph.enemy.id == id)
+            })
+            .count();
+        let next = alive == 0
+            || match waves.trigger {
+                WaveTrigger::Timer(delay) => waves.time >= delay,
+                WaveTrigger::Alive(fraction) => {
+                    r32(alive as f32) < fraction * r32(waves.current.len() as f32)
+                }
+            };
+        if next {
+            self.next_wave();
+            if self.room_clear() && self.challenge.is_none() && self.player.health.is_above_min() {
+                // The remaining waves turned out empty
+                self.finish_battle();
+            }
+        }
+    }
+
+    /// Whether there are no enemies left in the room, including the ones yet to arrive.
+    pub fn room_clear(&self) -> bool {
+        self.enemies.is_empty()
+            && self.spawn_queue.is_empty()
+            && self.spawn_telegraphs.is_empty()
+            && self.waves.is_none()
+    }
+}
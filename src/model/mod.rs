@@ -1,10 +1,26 @@
 mod collider;
+mod daily;
+mod endless;
 mod enemy;
+mod explosion;
 mod id;
 mod logic;
+mod mode;
+mod mutator;
+mod navigation;
+mod obstacle;
 mod particles;
-
-pub use self::{collider::*, enemy::*, id::*, particles::*};
+mod raycast;
+mod status;
+mod unlock;
+mod upgrade;
+mod wave;
+
+pub use self::{
+    collider::*, daily::*, endless::*, enemy::*, explosion::*, id::*, mode::*, mutator::*,
+    navigation::*, obstacle::*, particles::*, raycast::*, status::*, unlock::*, upgrade::*,
+    wave::*,
+};
 
 use crate::prelude::*;
 
@@ -16,16 +32,31 @@ pub type Hp = R32;
 pub type Health = Bounded<Hp>;
 pub type Score = u64;
 
+/// Default camera field of view.
+pub const CAMERA_FOV: f32 = 30.0;
+
 pub struct Model {
     pub config: Config,
+    pub options: RunOptions,
+    /// Generator of the run layout, seeded from the options.
+    pub rng: StdRng,
     pub camera: Camera,
     pub real_time: Time,
     pub game_time: Time,
     pub cursor_pos: Position,
     pub id_gen: IdGenerator,
 
+    /// Remaining time in the time attack mode.
+    pub time_left: Time,
     pub rooms_cleared: usize,
     pub bosses_killed: usize,
+    /// Rerolls of the offered upgrades left.
+    pub rerolls: usize,
+    /// Banishes of an offered upgrade left.
+    pub banishes: usize,
+    /// Upgrades that are never offered again this run.
+    pub banished: Vec<UpgradeEffect>,
+    pub endless_modifiers: Vec<EndlessModifier>,
     pub difficulty_raw: R32,
     pub difficulty: R32,
     pub score: Score,
@@ -33,16 +64,37 @@ pub struct Model {
 
     pub player: Player,
     pub rooms: Arena<Room>,
+    /// Room the run starts in.
+    pub starting_room: Index,
     pub room_colliders: Vec<(Index, Direction, Collider)>,
+    /// Direction of the most recently unlocked room relative to its parent.
+    pub last_unlocked: Option<Direction>,
+    pub compression_time: Time,
+    /// Walls currently moving inwards.
+    pub closing_walls: Vec<(Index, Direction)>,
+    /// Rooms about to be squashed.
+    pub squash_warnings: Vec<Index>,
+    pub navigation: NavGraph,
     pub objects: Vec<Object>,
+    pub obstacles: Vec<Obstacle>,
     pub minions: Vec<Minion>,
     pub enemies: Collection<Enemy>,
     pub upgrades: Vec<Upgrade>,
     pub particles: Arena<Particle>,
 
     pub pacman_1ups: Vec<Pacman1Up>,
+    pub snake_food: Vec<SnakeFood>,
+    pub painter_strokes: Vec<PainterStroke>,
+    pub boss_encounter: Option<BossEncounter>,
+    /// Challenge room the player is currently surviving.
+    pub challenge: Option<Challenge>,
+    pub waves: Option<RoomWaves>,
+    pub spawn_telegraphs: Vec<SpawnTelegraph>,
+    pub echoes: Vec<WhipEcho>,
+    pub trails: Vec<DashTrail>,
 
     pub particles_queue: Vec<SpawnParticles>,
+    pub explosions: Vec<Explosion>,
     pub spawn_queue: Vec<Enemy>,
     pub events: Vec<Event>,
 }
@@ -50,6 +102,9 @@ pub struct Model {
 #[derive(Debug)]
 pub enum Event {
     Sound(SoundEvent),
+    /// The first room after the starting one was unlocked.
+    RunStarted,
+    GameOver,
 }
 
 #[derive(Debug)]
@@ -63,36 +118,268 @@ pub enum SoundEvent {
     Minigun,
     Helicopter,
     Explosion,
+    SquashWarning,
+}
+
+#[derive(Debug, Clone)]
+pub struct BossEncounter {
+    pub name: String,
+    pub intro: Bounded<Time>,
+}
+
+impl BossEncounter {
+    pub fn in_intro(&self) -> bool {
+        self.intro.is_above_min()
+    }
+}
+
+/// Challenge room in progress.
+#[derive(Debug, Clone)]
+pub struct Challenge {
+    pub room: Index,
+    /// Difficulty budget of each wave.
+    pub difficulty: R32,
+    /// Time left to survive.
+    pub timer: Bounded<Time>,
+    /// Time until the next wave.
+    pub next_wave: Bounded<Time>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Room {
+    /// Bounding box of the room.
     pub area: Aabb2<Coord>,
+    pub shape: RoomShape,
+    /// The shape stretched over the area, kept to avoid recomputing it every frame.
+    pub floor: Vec<Aabb2<Coord>>,
+    pub kind: RoomKind,
+    /// Multiplier of the difficulty budget and the score for clearing the room.
+    pub difficulty: R32,
+    /// Upgrade guaranteed to be offered in the room.
+    pub reward: Option<UpgradeEffect>,
     /// Index of the room the player unlocked this room from.
     pub unlocked_after: Option<(Index, Direction)>,
     pub expanded_direction: Option<Direction>,
+    /// Rooms waiting behind each side, rolled when the room is generated.
+    pub previews: Vec<(Direction, RoomPreview)>,
+}
+
+/// Pre-rolled room behind a breakable side.
+#[derive(Debug, Clone)]
+pub struct RoomPreview {
+    pub size: vec2<Coord>,
+    pub shape: RoomShape,
+    pub kind: RoomKind,
+    pub difficulty: R32,
+    pub reward: Option<UpgradeEffect>,
+}
+
+/// What the room holds for the player, decided before it is unlocked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoomKind {
+    /// Defeat all enemies to get an upgrade.
+    Combat,
+    Boss,
+    /// A free upgrade and no enemies.
+    Treasure,
+    /// Upgrades bought with score.
+    Shop,
+    /// Survive waves of enemies until the timer runs out.
+    Challenge,
+    /// A healing fountain and no enemies.
+    Rest,
+}
+
+impl RoomKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RoomKind::Combat => "COMBAT",
+            RoomKind::Boss => "BOSS",
+            RoomKind::Treasure => "TREASURE",
+            RoomKind::Shop => "SHOP",
+            RoomKind::Challenge => "CHALLENGE",
+            RoomKind::Rest => "REST",
+        }
+    }
+
+    pub fn is_special(&self) -> bool {
+        !matches!(self, RoomKind::Combat | RoomKind::Boss)
+    }
+}
+
+/// Layout of the floor inside the bounding box of a room.
+/// Every shape covers the middle of each side, so that neighbouring rooms can connect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoomShape {
+    Rectangle,
+    /// Rectangle with a corner cut out, top-right unless flipped.
+    LShape {
+        flip_x: bool,
+        flip_y: bool,
+    },
+    /// Rectangle with all corners cut out.
+    Cross,
+    /// Circular arena made of horizontal strips.
+    Circle,
+}
+
+impl RoomShape {
+    /// The floor as a union of rectangles inside the unit square.
+    pub fn parts(&self) -> Vec<Aabb2<f32>> {
+        let rect = |min_x, min_y, max_x, max_y| Aabb2 {
+            min: vec2(min_x, min_y),
+            max: vec2(max_x, max_y),
+        };
+        match *self {
+            RoomShape::Rectangle => vec![rect(0.0, 0.0, 1.0, 1.0)],
+            RoomShape::LShape { flip_x, flip_y } => {
+                let cut = 0.4;
+                let flip = |v: f32, flip: bool| if flip { 1.0 - v } else { v };
+                [
+                    rect(0.0, 0.0, 1.0, 1.0 - cut),
+                    rect(0.0, 1.0 - cut, 1.0 - cut, 1.0),
+                ]
+                .into_iter()
+                .map(|part| {
+                    let a = vec2(flip(part.min.x, flip_x), flip(part.min.y, flip_y));
+                    let b = vec2(flip(part.max.x, flip_x), flip(part.max.y, flip_y));
+                    Aabb2::from_corners(a, b)
+                })
+                .collect()
+            }
+            RoomShape::Cross => {
+                let cut = 0.3;
+                vec![
+                    rect(0.0, cut, 1.0, 1.0 - cut),
+                    rect(cut, 0.0, 1.0 - cut, 1.0),
+                ]
+            }
+            RoomShape::Circle => {
+                let strips = 7;
+                (0..strips)
+                    .map(|i| {
+                        let min_y = i as f32 / strips as f32;
+                        let max_y = (i + 1) as f32 / strips as f32;
+                        // Distance from the center to the inner edge of the strip
+                        let inner = if min_y < 0.5 && max_y > 0.5 {
+                            0.0
+                        } else {
+                            (min_y * 2.0 - 1.0).abs().min((max_y * 2.0 - 1.0).abs())
+                        };
+                        let half_width = (1.0 - inner * inner).sqrt() / 2.0;
+                        rect(0.5 - half_width, min_y, 0.5 + half_width, max_y)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// The floor stretched over the area.
+    pub fn parts_in(&self, area: Aabb2<Coord>) -> Vec<Aabb2<Coord>> {
+        // Keep the outer edges exact so that neighbouring walls line up
+        let lerp = |t: f32, min: Coord, max: Coord| {
+            if t >= 1.0 {
+                max
+            } else {
+                min + (max - min) * r32(t)
+            }
+        };
+        self.parts()
+            .into_iter()
+            .map(|part| Aabb2 {
+                min: vec2(
+                    lerp(part.min.x, area.min.x, area.max.x),
+                    lerp(part.min.y, area.min.y, area.max.y),
+                ),
+                max: vec2(
+                    lerp(part.max.x, area.min.x, area.max.x),
+                    lerp(part.max.y, area.min.y, area.max.y),
+                ),
+            })
+            .collect()
+    }
 }
 
 impl Room {
+    /// The floor of the room as a union of rectangles.
+    pub fn parts(&self) -> &[Aabb2<Coord>] {
+        &self.floor
+    }
+
+    /// Bounding box of a room of the given size attached to the middle of a side.
+    pub fn neighbour_area(&self, direction: Direction, size: vec2<Coord>) -> Aabb2<Coord> {
+        let area = self.area;
+        match direction {
+            Direction::Left => Aabb2::point(vec2(area.min.x, area.center().y))
+                .extend_left(size.x)
+                .extend_symmetric(vec2(Coord::ZERO, size.y) / r32(2.0)),
+            Direction::Right => Aabb2::point(vec2(area.max.x, area.center().y))
+                .extend_right(size.x)
+                .extend_symmetric(vec2(Coord::ZERO, size.y) / r32(2.0)),
+            Direction::Down => Aabb2::point(vec2(area.center().x, area.min.y))
+                .extend_down(size.y)
+                .extend_symmetric(vec2(size.x, Coord::ZERO) / r32(2.0)),
+            Direction::Up => Aabb2::point(vec2(area.center().x, area.max.y))
+                .extend_up(size.y)
+                .extend_symmetric(vec2(size.x, Coord::ZERO) / r32(2.0)),
+        }
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        self.parts().iter().any(|part| part.contains(pos))
+    }
+
+    /// A random position on the floor at least `margin` away from the bounding box.
+    pub fn random_position(&self, margin: Coord, rng: &mut impl Rng) -> Option<Position> {
+        let area = self.area.extend_uniform(-margin);
+        for _ in 0..20 {
+            let position = vec2(
+                rng.gen_range(area.min.x..=area.max.x),
+                rng.gen_range(area.min.y..=area.max.y),
+            );
+            let inside = [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)]
+                .into_iter()
+                .all(|(x, y)| self.contains(position + vec2(r32(x), r32(y)) * margin));
+            if inside {
+                return Some(position);
+            }
+        }
+        None
+    }
+
     pub fn closest_wall(&self, pos: Position) -> (Coord, Direction) {
+        // Walls of the closest part of the floor
+        let area = self
+            .parts()
+            .iter()
+            .copied()
+            .min_by_key(|part| {
+                let clamped = vec2(
+                    pos.x.clamp(part.min.x, part.max.x),
+                    pos.y.clamp(part.min.y, part.max.y),
+                );
+                (pos - clamped).len()
+            })
+            .unwrap_or(self.area);
+
         let mut dist = r32(9999999999.0);
         let mut closest = Direction::Left;
-        let left = self.area.min.x - pos.x;
+        let left = area.min.x - pos.x;
         if left > Coord::ZERO && left < dist {
             dist = left;
             closest = Direction::Left;
         }
-        let right = pos.x - self.area.max.x;
+        let right = pos.x - area.max.x;
         if right > Coord::ZERO && right < dist {
             dist = right;
             closest = Direction::Right;
         }
-        let bottom = self.area.min.y - pos.y;
+        let bottom = area.min.y - pos.y;
         if bottom > Coord::ZERO && bottom < dist {
             dist = bottom;
             closest = Direction::Down;
         }
-        let top = pos.y - self.area.max.y;
+        let top = pos.y - area.max.y;
         if top > Coord::ZERO && top < dist {
             dist = top;
             closest = Direction::Up;
@@ -132,30 +419,77 @@ impl Direction {
 #[derive(Debug, Clone)]
 pub struct Object {
     pub dead: bool,
+    /// Time until a chain reaction sets the object off.
+    pub fuse: Option<Time>,
     pub collider: Collider,
     pub kind: ObjectKind,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub enum ObjectKind {
-    ExplosiveBarrel { range: Coord, damage: Hp },
+    ExplosiveBarrel {
+        range: Coord,
+        damage: Hp,
+    },
+    /// Stuns the enemies around when destroyed.
+    FreezeBarrel {
+        range: Coord,
+        stun: Time,
+    },
+    /// Shoots the closest enemies after being drawn over.
+    Turret {
+        range: Coord,
+        damage: Hp,
+        speed: Coord,
+        cooldown: Bounded<Time>,
+        shots: usize,
+        /// Shots left until the next activation.
+        charges: usize,
+    },
+    /// Launches anything that steps on it.
+    BouncePad {
+        strength: Coord,
+    },
+    /// Heals the player on touch a limited number of times.
+    HealingFountain {
+        heal: Hp,
+        uses: usize,
+        cooldown: Bounded<Time>,
+    },
+    /// Moves the player to the linked teleporter.
+    Teleporter {
+        exit: Position,
+        cooldown: Bounded<Time>,
+    },
+    /// Solid box that the player can push around.
+    Crate,
 }
 
-#[derive(Debug, Clone)]
-pub struct Upgrade {
-    pub collider: Collider,
-    pub effect: UpgradeEffect,
-}
+impl ObjectKind {
+    pub fn is_explosive(&self) -> bool {
+        matches!(
+            self,
+            ObjectKind::ExplosiveBarrel { .. } | ObjectKind::FreezeBarrel { .. }
+        )
+    }
 
-#[derive(Debug, Clone)]
-pub enum UpgradeEffect {
-    Width,
-    Range,
-    Damage,
-    Speed,
-    // Heal,
-    Difficulty,
-    Weapon(Weapon),
+    /// Whether the player has to walk around (or push) the object.
+    pub fn is_solid(&self) -> bool {
+        !matches!(
+            self,
+            ObjectKind::BouncePad { .. }
+                | ObjectKind::HealingFountain { .. }
+                | ObjectKind::Teleporter { .. }
+        )
+    }
+
+    pub fn shape(&self) -> Shape {
+        if self.is_solid() {
+            Shape::square(r32(1.33))
+        } else {
+            Shape::circle(r32(0.8))
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -195,6 +529,12 @@ pub enum MinionAI {
         damage: Hp,
         explosion_damage: Hp,
         explosion_radius: Coord,
+        effects: Vec<StatusEffectConfig>,
+        explosion_effects: Vec<StatusEffectConfig>,
+        /// Flies through the enemies instead of stopping at the first one.
+        pierce: bool,
+        /// Enemies already hit by the piercing bullet.
+        hit: Vec<Id>,
     },
 }
 
@@ -207,9 +547,23 @@ pub struct Player {
     pub stats: PlayerConfig,
     pub invincibility: Bounded<Time>,
     pub draw_action: Option<Drawing>,
+    pub status: StatusEffects,
+    /// Every upgrade collected this run.
+    pub upgrades: Vec<UpgradeEffect>,
+    pub synergies: Vec<Synergy>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Player {
+    pub fn has_upgrade(&self, effect: &UpgradeEffect) -> bool {
+        self.upgrades.contains(effect)
+    }
+
+    pub fn has_synergy(&self, synergy: Synergy) -> bool {
+        self.synergies.contains(&synergy)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Weapon {
     Whip,
     Dash,
@@ -245,68 +599,208 @@ pub struct PlayerControls {
 }
 
 impl Model {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, options: RunOptions) -> Self {
         let mut rooms = Arena::new();
-        rooms.insert(Room {
-            area: Aabb2::ZERO.extend_symmetric(config.starting_area / r32(2.0)),
+        let starting_area = Aabb2::ZERO.extend_symmetric(config.starting_area / r32(2.0));
+        let starting_room = rooms.insert(Room {
+            area: starting_area,
+            shape: RoomShape::Rectangle,
+            floor: RoomShape::Rectangle.parts_in(starting_area),
+            kind: RoomKind::Combat,
+            difficulty: R32::ONE,
+            reward: None,
             unlocked_after: None,
             expanded_direction: None,
+            previews: Vec::new(),
         });
 
+        let mut player = config.player.clone();
+        for unlock in &options.unlocks {
+            unlock.apply_player(&mut player);
+        }
+        let mut score_multiplier = R32::ONE;
+        for mutator in &options.mutators {
+            mutator.apply_player(&mut player);
+            score_multiplier *= config.mutator_score_multiplier(mutator);
+        }
+
         let mut model = Self {
             camera: Camera {
                 center: vec2::ZERO,
                 rotation: Angle::ZERO,
-                fov: 30.0,
+                fov: CAMERA_FOV,
             },
             real_time: Time::ZERO,
             game_time: Time::ZERO,
             cursor_pos: vec2::ZERO,
             id_gen: IdGenerator::new(),
 
+            time_left: config.modes.time_attack,
             rooms_cleared: 0,
             bosses_killed: 0,
+            rerolls: config.upgrade_choices.rerolls,
+            banishes: config.upgrade_choices.banishes,
+            banished: Vec::new(),
+            endless_modifiers: Vec::new(),
             difficulty_raw: config.difficulty.initial,
             difficulty: config.difficulty.initial,
             score: 0,
-            score_multiplier: R32::ONE,
+            score_multiplier,
 
             player: Player {
-                health: Health::new_max(config.player.health),
+                health: Health::new_max(player.health),
                 last_hit: r32(-999.0),
-                body: PhysicsBody::new(vec2::ZERO, config.player.shape),
-                active_weapon: Weapon::Whip,
-                stats: config.player.clone(),
+                body: PhysicsBody::new(vec2::ZERO, player.shape),
+                active_weapon: options.starting_weapon,
                 invincibility: Bounded::new_zero(
-                    config
-                        .player
+                    player
                         .dash
                         .invincibility_time
-                        .max(config.player.hurt_invincibility_time),
+                        .max(player.hurt_invincibility_time),
                 ),
+                stats: player,
                 draw_action: None,
+                status: StatusEffects::new(),
+                upgrades: Vec::new(),
+                synergies: Vec::new(),
             },
             rooms,
+            starting_room,
             room_colliders: Vec::new(),
+            last_unlocked: None,
+            compression_time: Time::ZERO,
+            closing_walls: Vec::new(),
+            squash_warnings: Vec::new(),
+            navigation: NavGraph::default(),
             objects: Vec::new(),
+            obstacles: Vec::new(),
             minions: Vec::new(),
             enemies: Collection::new(),
             upgrades: Vec::new(),
             particles: Arena::new(),
 
             pacman_1ups: Vec::new(),
+            snake_food: Vec::new(),
+            painter_strokes: Vec::new(),
+            boss_encounter: None,
+            challenge: None,
+            waves: None,
+            spawn_telegraphs: Vec::new(),
+            echoes: Vec::new(),
+            trails: Vec::new(),
 
             particles_queue: Vec::new(),
+            explosions: Vec::new(),
             spawn_queue: Vec::new(),
             events: Vec::new(),
 
+            rng: StdRng::seed_from_u64(options.seed),
             config,
+            options,
         };
+        for mutator in &model.options.mutators {
+            if let &Mutator::StartingWeapon(weapon) = mutator {
+                model.player.active_weapon = weapon;
+            }
+        }
+        let previews = [
+            Direction::Left,
+            Direction::Right,
+            Direction::Down,
+            Direction::Up,
+        ]
+        .into_iter()
+        .map(|direction| (direction, model.roll_preview(RoomKind::Combat)))
+        .collect();
+        if let Some(room) = model.rooms.get_mut(model.starting_room) {
+            room.previews = previews;
+        }
         model.update_room_colliders();
         model
     }
 
+    /// The endless loop of the room currently being fought in, 0 before the final boss.
+    pub fn endless_loop(&self) -> usize {
+        match self.options.mode {
+            GameMode::Normal => self.config.endless_loop(self.rooms_cleared + 1),
+            _ => 0,
+        }
+    }
+
+    /// The boss guarding the next room, along with its endless loop.
+    pub fn room_boss(&self) -> Option<(BossConfig, usize)> {
+        match self.options.mode {
+            GameMode::BossRush => self
+                .config
+                .bosses
+                .get(self.rooms_cleared)
+                .map(|boss| (boss.clone(), 0)),
+            GameMode::TimeAttack => None,
+            _ => self
+                .config
+                .boss_at(self.rooms_cleared + 1)
+                .map(|(boss, endless_loop)| (boss.clone(), endless_loop)),
+        }
+    }
+
+    /// Number of rooms to clear before the next boss room, if there is one.
+    pub fn rooms_until_boss(&self) -> Option<usize> {
+        match self.options.mode {
+            GameMode::BossRush => (self.rooms_cleared < self.config.bosses.len()).then_some(0),
+            GameMode::TimeAttack => None,
+            _ => {
+                let cycle = self.config.endless.cycle.max(self.config.bosses.len());
+                (0..=cycle).find(|i| self.config.boss_at(self.rooms_cleared + 1 + i).is_some())
+            }
+        }
+    }
+
+    /// The room the player unlocks by breaking the side of the room.
+    pub fn room_preview(&self, room_idx: Index, direction: Direction) -> Option<RoomPreview> {
+        if let Some((boss, _)) = self.room_boss() {
+            return Some(RoomPreview {
+                size: boss.room_size,
+                shape: RoomShape::Rectangle,
+                kind: RoomKind::Boss,
+                difficulty: R32::ONE,
+                reward: None,
+            });
+        }
+        let room = self.rooms.get(room_idx)?;
+        room.previews
+            .iter()
+            .find(|(side, _)| *side == direction)
+            .map(|(_, preview)| preview.clone())
+    }
+
+    pub fn has_mutator(&self, mutator: &Mutator) -> bool {
+        self.options.mutators.contains(mutator)
+    }
+
+    /// Restore the player's health, unless healing is disabled.
+    pub fn heal_player(&mut self, amount: Hp) {
+        if self.has_mutator(&Mutator::NoHeal) {
+            return;
+        }
+        self.player.health.change(amount);
+    }
+
+    /// Whether the run has ended, either by death or by the rules of the mode.
+    pub fn is_game_over(&self) -> bool {
+        self.player.health.is_min()
+            || match self.options.mode {
+                GameMode::TimeAttack => self.time_left <= Time::ZERO,
+                GameMode::BossRush => self.bosses_killed >= self.config.bosses.len(),
+                _ => false,
+            }
+    }
+
+    /// Whether the run has not left the starting room yet.
+    pub fn in_starting_room(&self) -> bool {
+        self.rooms.contains(self.starting_room) && self.rooms.len() == 1
+    }
+
     pub fn reset(&mut self) {
-        *self = Self::new(self.config.clone());
+        *self = Self::new(self.config.clone(), self.options.retry());
     }
 }
@@ -0,0 +1,79 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Normal,
+    /// Clear as many rooms as possible before the time runs out.
+    TimeAttack,
+    /// Fight the bosses one after another.
+    BossRush,
+    /// Drawing deals no damage, enemies have to be lured into barrels.
+    Pacifist,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Normal,
+        GameMode::TimeAttack,
+        GameMode::BossRush,
+        GameMode::Pacifist,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Normal => "Normal",
+            GameMode::TimeAttack => "Time Attack",
+            GameMode::BossRush => "Boss Rush",
+            GameMode::Pacifist => "Pacifist",
+        }
+    }
+}
+
+/// Everything that defines a run before it starts.
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    pub mode: GameMode,
+    /// Seed for the generation of rooms, enemies and upgrades.
+    pub seed: u64,
+    pub mutators: Vec<Mutator>,
+    pub daily: Option<DailyChallenge>,
+    /// Permanent unlocks applied to the player and the upgrade pool.
+    pub unlocks: Vec<Unlock>,
+    pub starting_weapon: Weapon,
+}
+
+impl RunOptions {
+    pub fn new(mode: GameMode) -> Self {
+        Self {
+            mode,
+            seed: thread_rng().gen(),
+            mutators: Vec::new(),
+            daily: None,
+            unlocks: Vec::new(),
+            starting_weapon: Weapon::Whip,
+        }
+    }
+
+    pub fn daily(challenge: DailyChallenge, config: &Config) -> Self {
+        Self {
+            mode: GameMode::Normal,
+            seed: challenge.seed(),
+            mutators: challenge.mutators(config),
+            daily: Some(challenge),
+            unlocks: Vec::new(),
+            starting_weapon: Weapon::Whip,
+        }
+    }
+
+    /// Options for another attempt at the same run.
+    pub fn retry(&self) -> Self {
+        match self.daily {
+            Some(_) => self.clone(),
+            None => Self {
+                seed: thread_rng().gen(),
+                ..self.clone()
+            },
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use super::*;
+
+/// Rule change applied for the whole run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Mutator {
+    StartingWeapon(Weapon),
+    /// Multiplies the speed of the enemies.
+    EnemySpeed(R32),
+    /// The player cannot restore health.
+    NoHeal,
+    /// Half the health, double the damage.
+    GlassCannon,
+    /// Rooms shrink twice as fast.
+    FastCompression,
+    /// Enemies explode on death, hurting the player.
+    ExplodingEnemies {
+        range: Coord,
+        damage: Hp,
+    },
+    /// Only weapons are offered as upgrades.
+    WeaponsOnly,
+    /// Horizontal movement is mirrored.
+    MirrorControls,
+}
+
+impl Mutator {
+    pub fn apply_enemy(&self, config: &mut EnemyConfig) {
+        if let Mutator::EnemySpeed(multiplier) = *self {
+            config.speed *= multiplier;
+            config.acceleration *= multiplier;
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Mutator::StartingWeapon(weapon) => format!("Start with {:?}", weapon),
+            Mutator::EnemySpeed(multiplier) => format!("Enemy speed x{:.1}", multiplier),
+            Mutator::NoHeal => "No heal".to_string(),
+            Mutator::GlassCannon => "Glass cannon".to_string(),
+            Mutator::FastCompression => "Fast compression".to_string(),
+            Mutator::ExplodingEnemies { .. } => "Exploding enemies".to_string(),
+            Mutator::WeaponsOnly => "Weapons only".to_string(),
+            Mutator::MirrorControls => "Mirror controls".to_string(),
+        }
+    }
+
+    pub fn apply_player(&self, config: &mut PlayerConfig) {
+        if let Mutator::GlassCannon = self {
+            config.health /= r32(2.0);
+            for weapon in [Weapon::Whip, Weapon::Dash, Weapon::Bow, Weapon::FishingRod] {
+                config.weapon_mut(weapon).damage *= r32(2.0);
+            }
+        }
+    }
+}
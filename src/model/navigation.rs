@@ -0,0 +1,116 @@
+use super::*;
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// Connectivity graph of the rooms, used by the enemies to find their way around walls.
+#[derive(Debug, Clone, Default)]
+pub struct NavGraph {
+    pub doorways: Vec<Doorway>,
+}
+
+/// An opening in the wall between two adjacent rooms.
+#[derive(Debug, Clone)]
+pub struct Doorway {
+    pub rooms: (Index, Index),
+    /// Center of the opening.
+    pub position: Position,
+    /// Direction of the passage from the first room to the second one.
+    pub direction: Direction,
+    pub width: Coord,
+}
+
+impl Doorway {
+    /// Returns the room on the other side of the doorway.
+    pub fn other(&self, room: Index) -> Option<Index> {
+        if self.rooms.0 == room {
+            Some(self.rooms.1)
+        } else if self.rooms.1 == room {
+            Some(self.rooms.0)
+        } else {
+            None
+        }
+    }
+
+    /// A point just past the opening when entering the given room.
+    pub fn waypoint(&self, entering: Index) -> Position {
+        let direction = if entering == self.rooms.1 {
+            self.direction.as_vec2()
+        } else {
+            -self.direction.as_vec2()
+        };
+        self.position + direction
+    }
+}
+
+impl NavGraph {
+    /// Find the shortest path from `start` in room `from` to `goal` in room `to`
+    /// using A* over the doorways. The path ends with the `goal` itself.
+    pub fn find_path(
+        &self,
+        from: Index,
+        start: Position,
+        to: Index,
+        goal: Position,
+    ) -> Option<Vec<Position>> {
+        if from == to {
+            return Some(vec![goal]);
+        }
+
+        // Each node is a doorway, along with the room it leads into
+        type Node = (usize, Index);
+        let mut open: BinaryHeap<Reverse<(Coord, Coord, Node)>> = BinaryHeap::new();
+        let mut cost: HashMap<Node, Coord> = HashMap::new();
+        let mut came_from: HashMap<Node, Option<Node>> = HashMap::new();
+
+        for (i, door) in self.doorways.iter().enumerate() {
+            if let Some(room) = door.other(from) {
+                let node = (i, room);
+                let g = (door.position - start).len();
+                cost.insert(node, g);
+                came_from.insert(node, None);
+                open.push(Reverse((g + (goal - door.position).len(), g, node)));
+            }
+        }
+
+        while let Some(Reverse((_, g, current))) = open.pop() {
+            if cost.get(&current).map_or(false, |&best| g > best) {
+                // Already reached cheaper
+                continue;
+            }
+            let (door_idx, room) = current;
+            if room == to {
+                let mut path = vec![goal];
+                let mut node = Some(current);
+                while let Some((i, entering)) = node {
+                    path.push(self.doorways[i].waypoint(entering));
+                    node = came_from[&(i, entering)];
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            let door = &self.doorways[door_idx];
+            for (i, next) in self.doorways.iter().enumerate() {
+                if i == door_idx {
+                    continue;
+                }
+                let Some(next_room) = next.other(room) else {
+                    continue;
+                };
+                let node = (i, next_room);
+                let new_cost = g + (next.position - door.position).len();
+                if cost.get(&node).map_or(true, |&old| new_cost < old) {
+                    cost.insert(node, new_cost);
+                    came_from.insert(node, Some(current));
+                    open.push(Reverse((
+                        new_cost + (goal - next.position).len(),
+                        new_cost,
+                        node,
+                    )));
+                }
+            }
+        }
+
+        None
+    }
+}
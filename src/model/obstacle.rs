@@ -0,0 +1,38 @@
+use super::*;
+
+/// Static terrain feature inside a room.
+#[derive(Debug, Clone)]
+pub struct Obstacle {
+    /// The room the obstacle was generated in.
+    pub room: Index,
+    pub collider: Collider,
+    pub kind: ObstacleKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ObstacleKind {
+    /// Blocks movement and shots.
+    Pillar,
+    /// Blocks movement, but shots fly over it.
+    LowWall,
+    /// Blocks movement, but enemies knocked into it fall to their death.
+    Pit,
+    /// Damage per second to anything standing on it.
+    Spikes { damage: Hp },
+    /// Slows down anything walking through it.
+    Mud { slow: R32 },
+}
+
+impl ObstacleKind {
+    /// Whether the player and enemies have to walk around the obstacle.
+    pub fn is_solid(&self) -> bool {
+        matches!(
+            self,
+            ObstacleKind::Pillar | ObstacleKind::LowWall | ObstacleKind::Pit
+        )
+    }
+
+    pub fn blocks_shots(&self) -> bool {
+        matches!(self, ObstacleKind::Pillar)
+    }
+}
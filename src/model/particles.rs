@@ -22,6 +22,8 @@ pub enum ParticleKind {
     HitSelf,
     Shield,
     Heal,
+    Telegraph,
+    Status(StatusKind),
 }
 
 #[derive(Debug, Clone)]
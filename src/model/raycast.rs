@@ -0,0 +1,41 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RaycastHit {
+    /// Distance from the origin of the ray to the hit point.
+    pub distance: Coord,
+    pub point: Position,
+    pub target: RaycastTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaycastTarget {
+    /// A wall of the room with the given index.
+    Wall(Index),
+    /// Index into the objects list.
+    Object(usize),
+    /// Index into the obstacles list.
+    Obstacle(usize),
+}
+
+/// Specifies what can block a ray.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaycastFilter {
+    pub walls: bool,
+    pub objects: bool,
+    /// Obstacles that block shots.
+    pub obstacles: bool,
+}
+
+impl RaycastFilter {
+    pub const WALLS: Self = Self {
+        walls: true,
+        objects: false,
+        obstacles: false,
+    };
+    pub const ALL: Self = Self {
+        walls: true,
+        objects: true,
+        obstacles: true,
+    };
+}
@@ -0,0 +1,114 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusKind {
+    Burn,
+    Slow,
+    Stun,
+    Poison,
+}
+
+impl StatusKind {
+    pub fn max_stacks(&self) -> usize {
+        match self {
+            StatusKind::Burn => 3,
+            StatusKind::Poison => 5,
+            StatusKind::Slow | StatusKind::Stun => 1,
+        }
+    }
+}
+
+/// An effect applied on hit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusEffectConfig {
+    pub kind: StatusKind,
+    pub duration: Time,
+    /// Damage per second per stack for burn and poison, fraction of the speed lost for slow.
+    #[serde(default)]
+    pub magnitude: R32,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub magnitude: R32,
+    pub stacks: usize,
+    pub duration: Bounded<Time>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.has(StatusKind::Stun)
+    }
+
+    /// Reapplying an active effect adds a stack (up to the limit),
+    /// refreshes the duration and keeps the strongest magnitude.
+    pub fn apply(&mut self, config: &StatusEffectConfig) {
+        match self
+            .effects
+            .iter_mut()
+            .find(|effect| effect.kind == config.kind)
+        {
+            Some(effect) => {
+                effect.stacks = (effect.stacks + 1).min(config.kind.max_stacks());
+                effect.magnitude = effect.magnitude.max(config.magnitude);
+                effect.duration = Bounded::new_max(effect.duration.value().max(config.duration));
+            }
+            None => self.effects.push(StatusEffect {
+                kind: config.kind,
+                magnitude: config.magnitude,
+                stacks: 1,
+                duration: Bounded::new_max(config.duration),
+            }),
+        }
+    }
+
+    /// Update the timers and return the damage dealt during this frame.
+    pub fn update(&mut self, delta_time: Time) -> Hp {
+        let mut damage = Hp::ZERO;
+        for effect in &mut self.effects {
+            if let StatusKind::Burn | StatusKind::Poison = effect.kind {
+                damage += effect.magnitude
+                    * r32(effect.stacks as f32)
+                    * delta_time.min(effect.duration.value());
+            }
+            effect.duration.change(-delta_time);
+        }
+        self.effects.retain(|effect| effect.duration.is_above_min());
+        damage
+    }
+
+    /// Multiplier for the movement speed.
+    pub fn speed_multiplier(&self) -> R32 {
+        if self.is_stunned() {
+            return R32::ZERO;
+        }
+        self.effects
+            .iter()
+            .filter(|effect| effect.kind == StatusKind::Slow)
+            .map(|effect| (R32::ONE - effect.magnitude).max(R32::ZERO))
+            .fold(R32::ONE, R32::mul)
+    }
+
+    /// Remove all effects.
+    pub fn cleanse(&mut self) {
+        self.effects.clear();
+    }
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+/// Permanent unlock bought with the coins earned across runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Unlock {
+    /// Weapon that can be picked before a run.
+    StartingWeapon(Weapon),
+    /// Upgrade added to the pool, not offered before it is unlocked.
+    Upgrade(UpgradeEffect),
+    Health(Hp),
+    Speed(Coord),
+    /// Extra damage of every weapon.
+    Damage(Hp),
+}
+
+impl Unlock {
+    pub fn name(&self) -> String {
+        match self {
+            Unlock::StartingWeapon(weapon) => format!("Start with {:?}", weapon),
+            Unlock::Upgrade(effect) => format!("{} upgrade", effect.name()),
+            Unlock::Health(amount) => format!("+{:.0} health", amount),
+            Unlock::Speed(amount) => format!("+{:.1} speed", amount),
+            Unlock::Damage(amount) => format!("+{:.1} damage", amount),
+        }
+    }
+
+    pub fn apply_player(&self, config: &mut PlayerConfig) {
+        match *self {
+            Unlock::Health(amount) => config.health += amount,
+            Unlock::Speed(amount) => config.speed += amount,
+            Unlock::Damage(amount) => {
+                for weapon in [Weapon::Whip, Weapon::Dash, Weapon::Bow, Weapon::FishingRod] {
+                    config.weapon_mut(weapon).damage += amount;
+                }
+            }
+            Unlock::StartingWeapon(_) | Unlock::Upgrade(_) => {}
+        }
+    }
+}
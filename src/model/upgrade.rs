@@ -0,0 +1,152 @@
+use super::*;
+
+#[derive(Debug, Clone)]
+pub struct Upgrade {
+    pub collider: Collider,
+    pub effect: UpgradeEffect,
+    /// Score to pay in the shop, free upgrades are a choice of one.
+    pub price: Option<Score>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UpgradeEffect {
+    Width,
+    Range,
+    Damage,
+    Speed,
+    // Heal,
+    Difficulty,
+    Weapon(Weapon),
+    /// Whip strokes set enemies on fire.
+    Ignite,
+    /// Bow arrows poison enemies.
+    Venom,
+    /// Bow arrows fly through the enemies.
+    Pierce,
+    /// Dash leaves a damaging trail behind.
+    Trail,
+    /// Whip strokes hit again after a delay.
+    Echo,
+    /// More max health, only offered once unlocked between runs.
+    Health,
+    /// Shorter cooldown of every weapon, only offered once unlocked between runs.
+    Cooldown,
+}
+
+impl UpgradeEffect {
+    pub fn name(&self) -> String {
+        match self {
+            UpgradeEffect::Width => "WIDTH".to_string(),
+            UpgradeEffect::Range => "RANGE".to_string(),
+            UpgradeEffect::Damage => "DAMAGE".to_string(),
+            UpgradeEffect::Speed => "SPEED".to_string(),
+            UpgradeEffect::Difficulty => "DIFFICULTY".to_string(),
+            UpgradeEffect::Weapon(weapon) => format!("{:?}", weapon).to_uppercase(),
+            UpgradeEffect::Ignite => "IGNITE".to_string(),
+            UpgradeEffect::Venom => "VENOM".to_string(),
+            UpgradeEffect::Pierce => "PIERCE".to_string(),
+            UpgradeEffect::Trail => "TRAIL".to_string(),
+            UpgradeEffect::Echo => "ECHO".to_string(),
+            UpgradeEffect::Health => "HEALTH".to_string(),
+            UpgradeEffect::Cooldown => "COOLDOWN".to_string(),
+        }
+    }
+
+    pub fn rarity(&self) -> Rarity {
+        match self {
+            UpgradeEffect::Width
+            | UpgradeEffect::Range
+            | UpgradeEffect::Damage
+            | UpgradeEffect::Speed
+            | UpgradeEffect::Health => Rarity::Common,
+            UpgradeEffect::Difficulty
+            | UpgradeEffect::Cooldown
+            | UpgradeEffect::Weapon(_)
+            | UpgradeEffect::Ignite
+            | UpgradeEffect::Venom => Rarity::Rare,
+            UpgradeEffect::Pierce | UpgradeEffect::Trail | UpgradeEffect::Echo => Rarity::Legendary,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rarity {
+    Common,
+    Rare,
+    /// Changes how a weapon behaves, can only be collected once.
+    Legendary,
+}
+
+/// Bonus for holding a specific combination of upgrades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Synergy {
+    /// Dash trail sets enemies on fire.
+    Wildfire,
+    /// Explosions of the arrows poison enemies.
+    Plague,
+    /// Echoes of the whip are wider.
+    Reverb,
+    /// Dash trail lasts longer.
+    Afterburn,
+}
+
+impl Synergy {
+    pub const ALL: [Synergy; 4] = [
+        Synergy::Wildfire,
+        Synergy::Plague,
+        Synergy::Reverb,
+        Synergy::Afterburn,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Synergy::Wildfire => "WILDFIRE",
+            Synergy::Plague => "PLAGUE",
+            Synergy::Reverb => "REVERB",
+            Synergy::Afterburn => "AFTERBURN",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Synergy::Wildfire => "TRAIL BURNS",
+            Synergy::Plague => "ARROW BLASTS POISON",
+            Synergy::Reverb => "WIDER ECHOES",
+            Synergy::Afterburn => "LONGER TRAIL",
+        }
+    }
+
+    /// Upgrades that have to be held at the same time.
+    pub fn requires(&self) -> [UpgradeEffect; 2] {
+        match self {
+            Synergy::Wildfire => [UpgradeEffect::Ignite, UpgradeEffect::Trail],
+            Synergy::Plague => [UpgradeEffect::Venom, UpgradeEffect::Pierce],
+            Synergy::Reverb => [UpgradeEffect::Width, UpgradeEffect::Echo],
+            Synergy::Afterburn => [UpgradeEffect::Speed, UpgradeEffect::Trail],
+        }
+    }
+}
+
+/// Repeat of a whip stroke waiting to hit.
+#[derive(Debug, Clone)]
+pub struct WhipEcho {
+    pub points: Vec<Position>,
+    pub width: Coord,
+    pub damage: Hp,
+    pub effects: Vec<StatusEffectConfig>,
+    pub delay: Time,
+}
+
+/// Path of a dash that keeps hurting the enemies.
+#[derive(Debug, Clone)]
+pub struct DashTrail {
+    pub points: Vec<Position>,
+    pub width: Coord,
+    /// Damage per second.
+    pub damage: Hp,
+    /// Applied once to every enemy touching the trail.
+    pub effects: Vec<StatusEffectConfig>,
+    /// Enemies that already got the effects.
+    pub affected: Vec<Id>,
+    pub lifetime: Bounded<Time>,
+}
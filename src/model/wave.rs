@@ -0,0 +1,31 @@
+use super::*;
+
+/// Enemy waves still to come in the current room.
+#[derive(Debug, Clone)]
+pub struct RoomWaves {
+    pub room: Index,
+    pub trigger: WaveTrigger,
+    /// Difficulty budget of each remaining wave.
+    pub budgets: Vec<R32>,
+    /// Enemies of the latest wave.
+    pub current: Vec<Id>,
+    /// Time since the latest wave started.
+    pub time: Time,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WaveTrigger {
+    /// Next wave after a delay.
+    Timer(Time),
+    /// Next wave once less than the fraction of the latest wave is alive.
+    Alive(R32),
+}
+
+/// Enemy about to appear, its spawn point is marked in advance.
+#[derive(Debug, Clone)]
+pub struct SpawnTelegraph {
+    pub enemy: Enemy,
+    pub timer: Bounded<Time>,
+    /// Telegraphs of the same group (formations, bosses) are released together.
+    pub group: Id,
+}
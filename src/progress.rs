@@ -0,0 +1,86 @@
+use crate::prelude::*;
+
+const STORAGE_KEY: &str = "progress";
+
+/// Coins and unlocks kept between runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetaProgress {
+    pub coins: u64,
+    pub unlocks: Vec<Unlock>,
+    /// Weapon picked for the next runs, whip if none.
+    pub starting_weapon: Option<Weapon>,
+}
+
+impl MetaProgress {
+    pub fn load() -> Self {
+        preferences::load(STORAGE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        preferences::save(STORAGE_KEY, self);
+    }
+
+    /// Forget all coins and unlocks.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Convert the score of a finished run into coins.
+    /// Returns the number of coins earned.
+    pub fn earn(&mut self, score: Score, config: &MetaConfig) -> u64 {
+        let coins = score / config.score_per_coin.max(1);
+        self.coins += coins;
+        coins
+    }
+
+    pub fn is_unlocked(&self, unlock: &Unlock) -> bool {
+        self.unlocks.contains(unlock)
+    }
+
+    /// Buy the unlock at the index in the config.
+    /// Returns whether it was bought.
+    pub fn buy(&mut self, index: usize, config: &MetaConfig) -> bool {
+        let Some(config) = config.unlocks.get(index) else {
+            return false;
+        };
+        if self.is_unlocked(&config.unlock) || self.coins < config.cost {
+            return false;
+        }
+        self.coins -= config.cost;
+        self.unlocks.push(config.unlock.clone());
+        true
+    }
+
+    pub fn starting_weapons(&self) -> Vec<Weapon> {
+        std::iter::once(Weapon::Whip)
+            .chain(self.unlocks.iter().filter_map(|unlock| match unlock {
+                Unlock::StartingWeapon(weapon) => Some(*weapon),
+                _ => None,
+            }))
+            .collect()
+    }
+
+    /// Switch to the next unlocked starting weapon.
+    pub fn next_weapon(&mut self) {
+        let weapons = self.starting_weapons();
+        let current = self.starting_weapon.unwrap_or(Weapon::Whip);
+        let next = weapons
+            .iter()
+            .position(|&weapon| weapon == current)
+            .map_or(0, |i| (i + 1) % weapons.len());
+        self.starting_weapon = Some(weapons[next]);
+    }
+
+    /// Apply the unlocks to the options of a new run.
+    pub fn apply(&self, options: &mut RunOptions) {
+        if options.daily.is_some() {
+            // Daily challenges are the same for everyone
+            return;
+        }
+        options.unlocks = self.unlocks.clone();
+        options.starting_weapon = self
+            .starting_weapon
+            .filter(|weapon| self.starting_weapons().contains(weapon))
+            .unwrap_or(Weapon::Whip);
+    }
+}
@@ -1,4 +1,9 @@
-use crate::prelude::*;
+use crate::{
+    history::RunHistory,
+    leaderboard::{Leaderboard, LeaderboardCategory, LeaderboardEntry},
+    prelude::*,
+    progress::MetaProgress,
+};
 
 pub struct SwapBuffer {
     ugli: Ugli,
@@ -61,15 +66,117 @@ impl GameRender {
     pub fn draw_game(&mut self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
         // Rooms
         for (_, room) in &model.rooms {
+            for &part in room.parts() {
+                self.geng.draw2d().quad(
+                    framebuffer,
+                    &model.camera,
+                    part.map(Coord::as_f32),
+                    self.floor_color(room.kind),
+                );
+            }
+        }
+
+        // Next room previews
+        let mut labels = Vec::new();
+        for (room_idx, dir, collider) in &model.room_colliders {
+            if !model.is_breakable_wall(*room_idx, *dir) {
+                continue;
+            }
+            let Some(preview) = model.room_preview(*room_idx, *dir) else {
+                continue;
+            };
+            let wall = collider.compute_aabb().map(Coord::as_f32);
+            let width = 1.0;
+            let strip = match dir {
+                Direction::Right => wall.extend_right(width),
+                Direction::Left => wall.extend_left(width),
+                Direction::Up => wall.extend_up(width),
+                Direction::Down => wall.extend_down(width),
+            };
             self.geng.draw2d().quad(
                 framebuffer,
                 &model.camera,
-                room.area.map(Coord::as_f32),
-                self.assets.palette.room,
+                strip,
+                self.floor_color(preview.kind),
             );
+            if preview.kind != RoomKind::Combat && !labels.contains(&(*room_idx, *dir)) {
+                labels.push((*room_idx, *dir));
+            }
+        }
+        for (room_idx, dir) in labels {
+            let (Some(room), Some(preview)) =
+                (model.rooms.get(room_idx), model.room_preview(room_idx, dir))
+            else {
+                continue;
+            };
+            let area = room.area.map(Coord::as_f32);
+            let side = match dir {
+                Direction::Right => vec2(area.max.x, area.center().y),
+                Direction::Left => vec2(area.min.x, area.center().y),
+                Direction::Up => vec2(area.center().x, area.max.y),
+                Direction::Down => vec2(area.center().x, area.min.y),
+            };
+            let (align, offset) = match dir {
+                Direction::Right => (
+                    vec2(geng::TextAlign::LEFT, geng::TextAlign::CENTER),
+                    vec2(1.5, 0.0),
+                ),
+                Direction::Left => (
+                    vec2(geng::TextAlign::RIGHT, geng::TextAlign::CENTER),
+                    vec2(-1.5, 0.0),
+                ),
+                Direction::Up => (
+                    vec2(geng::TextAlign::CENTER, geng::TextAlign::BOTTOM),
+                    vec2(0.0, 1.5),
+                ),
+                Direction::Down => (
+                    vec2(geng::TextAlign::CENTER, geng::TextAlign::TOP),
+                    vec2(0.0, -1.5),
+                ),
+            };
+            self.assets.font.draw(
+                framebuffer,
+                &model.camera,
+                preview.kind.name(),
+                align,
+                mat3::translate(side + offset),
+                self.assets.palette.text,
+            );
+        }
+
+        // Targeted room
+        if let Some((room_idx, dir)) = model.targeted_wall() {
+            if let (Some(room), Some(preview)) =
+                (model.rooms.get(room_idx), model.room_preview(room_idx, dir))
+            {
+                self.draw_room_preview(model, room, dir, &preview, framebuffer);
+            }
+        }
+
+        // Closing walls
+        for &(room_idx, dir) in &model.closing_walls {
+            let Some(room) = model.rooms.get(room_idx) else {
+                continue;
+            };
+            let area = room.area.map(Coord::as_f32);
+            let width = 0.3;
+            let strip = match dir {
+                Direction::Right => area.extend_right(width - area.width()),
+                Direction::Left => area.extend_left(width - area.width()),
+                Direction::Up => area.extend_up(width - area.height()),
+                Direction::Down => area.extend_down(width - area.height()),
+            };
+            let warning = model.squash_warnings.contains(&room_idx);
+            let frequency = if warning { 8.0 } else { 2.0 };
+            let t = (model.real_time.as_f32() * frequency * f32::PI).sin() * 0.5 + 0.5;
+            let mut color = self.assets.palette.damage;
+            color.a = if warning { 0.4 + 0.6 * t } else { 0.2 * t };
+            self.geng
+                .draw2d()
+                .quad(framebuffer, &model.camera, strip, color);
         }
 
-        if model.rooms.contains(Index::from_raw_parts(0, 0)) && model.rooms.len() == 1 {
+        if model.in_starting_room() {
             // Hint
             let pos = vec2(2.5, 3.7);
             if let Some(pos) = model
@@ -99,6 +206,26 @@ impl GameRender {
             }
         }
 
+        // Obstacles
+        for obstacle in &model.obstacles {
+            let color = match obstacle.kind {
+                ObstacleKind::Pillar => self.assets.palette.wall_block,
+                ObstacleKind::LowWall => {
+                    let mut color = self.assets.palette.wall_block;
+                    color.a = 0.6;
+                    color
+                }
+                ObstacleKind::Pit => self.assets.palette.background,
+                ObstacleKind::Spikes { .. } => self.assets.palette.damage,
+                ObstacleKind::Mud { .. } => {
+                    let mut color = self.assets.palette.slow;
+                    color.a = 0.5;
+                    color
+                }
+            };
+            self.draw_collider(&obstacle.collider, color, &model.camera, framebuffer);
+        }
+
         // Objects
         for object in &model.objects {
             let Some(pos) = model.camera.world_to_screen(
@@ -107,12 +234,76 @@ impl GameRender {
             ) else {
                 continue;
             };
+            let palette = &self.assets.palette;
+            // Flash while the fuse is burning
+            let lit = object.fuse.is_some() && (model.real_time.as_f32() * 20.0) as i64 % 2 == 0;
             match &object.kind {
                 ObjectKind::ExplosiveBarrel { .. } => {
                     self.draw_texture(
                         Aabb2::point(pos),
                         &self.assets.sprites.barrel,
-                        self.assets.palette.object,
+                        if lit { palette.text } else { palette.object },
+                        framebuffer,
+                    );
+                }
+                ObjectKind::FreezeBarrel { .. } => {
+                    self.draw_texture(
+                        Aabb2::point(pos),
+                        &self.assets.sprites.barrel,
+                        if lit { palette.text } else { palette.stun },
+                        framebuffer,
+                    );
+                }
+                ObjectKind::Turret { range, charges, .. } => {
+                    self.draw_collider(
+                        &object.collider,
+                        palette.minion,
+                        &model.camera,
+                        framebuffer,
+                    );
+                    if *charges > 0 {
+                        // Show the range while active
+                        let mut color = palette.minion;
+                        color.a = 0.2;
+                        self.geng.draw2d().draw2d(
+                            framebuffer,
+                            &model.camera,
+                            &draw2d::Ellipse::circle_with_cut(
+                                object.collider.position.as_f32(),
+                                range.as_f32() - 0.1,
+                                range.as_f32(),
+                                color,
+                            ),
+                        );
+                    }
+                }
+                ObjectKind::BouncePad { .. } => {
+                    self.draw_collider(
+                        &object.collider,
+                        palette.upgrade,
+                        &model.camera,
+                        framebuffer,
+                    );
+                }
+                ObjectKind::HealingFountain { cooldown, .. } => {
+                    let mut color = palette.health;
+                    if cooldown.is_above_min() {
+                        color.a = 0.5;
+                    }
+                    self.draw_collider(&object.collider, color, &model.camera, framebuffer);
+                }
+                ObjectKind::Teleporter { cooldown, .. } => {
+                    let mut color = palette.pacman_1up;
+                    if cooldown.is_above_min() {
+                        color.a = 0.5;
+                    }
+                    self.draw_collider(&object.collider, color, &model.camera, framebuffer);
+                }
+                ObjectKind::Crate => {
+                    self.draw_collider(
+                        &object.collider,
+                        palette.wall_block,
+                        &model.camera,
                         framebuffer,
                     );
                 }
@@ -149,12 +340,77 @@ impl GameRender {
             );
         }
 
+        // Dash trails
+        for trail in &model.trails {
+            if trail.points.len() < 2 {
+                continue;
+            }
+            let mut color = self.assets.palette.dash;
+            color.a = trail.lifetime.get_ratio().as_f32().min(0.5) * 2.0;
+            let chain = Chain::new(trail.points.iter().map(|pos| pos.as_f32()).collect());
+            self.geng.draw2d().draw2d(
+                framebuffer,
+                &model.camera,
+                &draw2d::Chain::new(chain, trail.width.as_f32() * 2.0, color, 3),
+            );
+        }
+
+        // Whip echoes
+        for echo in &model.echoes {
+            if echo.points.len() < 2 {
+                continue;
+            }
+            let mut color = self.assets.palette.drawing;
+            color.a = 0.5;
+            let chain = Chain::new(echo.points.iter().map(|pos| pos.as_f32()).collect());
+            self.geng.draw2d().draw2d(
+                framebuffer,
+                &model.camera,
+                &draw2d::Chain::new(chain, 0.1, color, 3),
+            );
+        }
+
+        // Painter strokes
+        for stroke in &model.painter_strokes {
+            let mut color = if stroke.blocking {
+                self.assets.palette.wall_block
+            } else {
+                self.assets.palette.enemy
+            };
+            let (points, width) = if stroke.is_active() {
+                color.a = stroke.lifetime.get_ratio().as_f32().min(0.5) * 2.0;
+                (stroke.points.len(), stroke.width.as_f32() * 2.0)
+            } else {
+                // Paint the telegraph gradually
+                let t = 1.0 - stroke.telegraph.get_ratio().as_f32();
+                color.a = 0.5;
+                ((stroke.points.len() as f32 * t).ceil() as usize, 0.1)
+            };
+            if points < 2 {
+                continue;
+            }
+            let chain = Chain::new(
+                stroke.points[..points]
+                    .iter()
+                    .map(|pos| pos.as_f32())
+                    .collect(),
+            );
+            self.geng.draw2d().draw2d(
+                framebuffer,
+                &model.camera,
+                &draw2d::Chain::new(chain, width, color, 3),
+            );
+        }
+
         // Enemies
         for enemy in &model.enemies {
             let hit_t = ((model.game_time - enemy.last_hit).as_f32() / 0.5).min(1.0);
             let hit_t = (1.0 - hit_t) * 2.5 + 1.0;
             let hit_color = Rgba::<f32>::WHITE.map_rgb(|x| x * hit_t);
-            let color = self.assets.palette.enemy * hit_color;
+            let color = match enemy.status.effects.last() {
+                Some(effect) => self.status_color(effect.kind),
+                None => self.assets.palette.enemy,
+            } * hit_color;
 
             match &enemy.ai {
                 EnemyAI::Pacman { pacman } => {
@@ -255,11 +511,50 @@ impl GameRender {
                         &draw2d::Segment::new(Segment(a, b), radius * 0.15, color),
                     );
                 }
+                EnemyAI::Snake { .. } => {
+                    self.draw_collider(&enemy.body.collider, color, &model.camera, framebuffer);
+                }
+                EnemyAI::SnakeSegment => {
+                    if let Some(ahead) =
+                        enemy.attached_to.and_then(|(id, _)| model.enemies.get(&id))
+                    {
+                        self.geng.draw2d().draw2d(
+                            framebuffer,
+                            &model.camera,
+                            &draw2d::Segment::new(
+                                Segment(
+                                    enemy.body.collider.position.as_f32(),
+                                    ahead.body.collider.position.as_f32(),
+                                ),
+                                0.1,
+                                color,
+                            ),
+                        );
+                    }
+                    self.draw_outline(&enemy.body.collider, 0.1, color, &model.camera, framebuffer);
+                }
                 _ => {
                     self.draw_outline(&enemy.body.collider, 0.1, color, &model.camera, framebuffer);
                 }
             }
-            if enemy.invincibility.is_above_min() {
+            if enemy.is_elite() {
+                let outline = Collider::new(
+                    enemy.body.collider.position,
+                    Shape::circle(
+                        enemy.body.collider.compute_aabb().size().len()
+                            / r32(std::f32::consts::SQRT_2 * 2.0)
+                            + r32(0.3),
+                    ),
+                );
+                self.draw_outline(
+                    &outline,
+                    0.05,
+                    self.assets.palette.elite,
+                    &model.camera,
+                    framebuffer,
+                );
+            }
+            if enemy.invincibility.is_above_min() && !matches!(enemy.ai, EnemyAI::SnakeSegment) {
                 let shield = Collider::new(
                     enemy.body.collider.position,
                     Shape::circle(
@@ -276,12 +571,15 @@ impl GameRender {
                     framebuffer,
                 );
             }
-            self.draw_health_bar(
-                &enemy.body.collider,
-                &enemy.health,
-                &model.camera,
-                framebuffer,
-            );
+            if !enemy.is_boss {
+                // NOTE: bosses have a dedicated bar in the ui
+                self.draw_health_bar(
+                    &enemy.body.collider,
+                    &enemy.health,
+                    &model.camera,
+                    framebuffer,
+                );
+            }
         }
 
         // 1up
@@ -294,6 +592,16 @@ impl GameRender {
             );
         }
 
+        // Snake food
+        for food in &model.snake_food {
+            self.draw_collider(
+                &food.collider,
+                self.assets.palette.snake_food,
+                &model.camera,
+                framebuffer,
+            );
+        }
+
         // Upgrades
         for upgrade in &model.upgrades {
             // self.draw_collider(
@@ -302,19 +610,7 @@ impl GameRender {
             //     &model.camera,
             //     framebuffer,
             // );
-            let texture = match &upgrade.effect {
-                UpgradeEffect::Width => &self.assets.sprites.width,
-                UpgradeEffect::Range => &self.assets.sprites.range,
-                UpgradeEffect::Damage => &self.assets.sprites.damage,
-                UpgradeEffect::Speed => &self.assets.sprites.speed,
-                UpgradeEffect::Difficulty => &self.assets.sprites.skull,
-                UpgradeEffect::Weapon(weapon) => match weapon {
-                    Weapon::Whip => &self.assets.sprites.whip,
-                    Weapon::Dash => &self.assets.sprites.dash,
-                    Weapon::Bow => &self.assets.sprites.bow,
-                    Weapon::FishingRod => &self.assets.sprites.fishing_rod,
-                },
-            };
+            let texture = self.upgrade_texture(&upgrade.effect);
 
             let pos = upgrade.collider.compute_aabb().center().as_f32();
             if let Some(pos) = model
@@ -322,7 +618,25 @@ impl GameRender {
                 .world_to_screen(framebuffer.size().as_f32(), pos)
             {
                 let quad = Aabb2::point(pos);
-                self.draw_texture(quad, texture, self.assets.palette.upgrade, framebuffer);
+                let color = self.rarity_color(upgrade.effect.rarity());
+                self.draw_texture(quad, texture, color, framebuffer);
+            }
+
+            if let Some(price) = upgrade.price {
+                let color = if price > model.score {
+                    self.assets.palette.damage
+                } else {
+                    self.assets.palette.text
+                };
+                self.assets.font.draw(
+                    framebuffer,
+                    &model.camera,
+                    &price.to_string(),
+                    vec2(geng::TextAlign::CENTER, geng::TextAlign::TOP),
+                    mat3::translate(upgrade.collider.position.as_f32() - vec2(0.0, 1.0))
+                        * mat3::scale_uniform(0.7),
+                    color,
+                );
             }
         }
 
@@ -340,9 +654,13 @@ impl GameRender {
                 framebuffer,
             );
         }
+        let player_color = match model.player.status.effects.last() {
+            Some(effect) => self.status_color(effect.kind),
+            None => self.assets.palette.player,
+        };
         self.draw_collider(
             &model.player.body.collider,
-            self.assets.palette.player,
+            player_color,
             &model.camera,
             framebuffer,
         );
@@ -368,6 +686,8 @@ impl GameRender {
                 ParticleKind::HitSelf => self.assets.palette.player,
                 ParticleKind::Shield => self.assets.palette.enemy,
                 ParticleKind::Heal => self.assets.palette.idk,
+                ParticleKind::Telegraph => self.assets.palette.enemy,
+                ParticleKind::Status(kind) => self.status_color(kind),
             };
             color.a = t;
             self.draw_collider_transformed(
@@ -397,6 +717,95 @@ impl GameRender {
         // }
     }
 
+    fn results_text(
+        &self,
+        model: &Model,
+        leaderboard: &Leaderboard,
+        history: &RunHistory,
+    ) -> String {
+        let title = if model.player.health.is_min() {
+            "You Died"
+        } else {
+            match model.options.mode {
+                GameMode::TimeAttack => "Time's Up",
+                GameMode::BossRush => "Victory",
+                _ => "Run Over",
+            }
+        };
+
+        let entry = LeaderboardEntry::from_model(model);
+        let best = leaderboard.best(entry.category).unwrap_or(model.score);
+        let bosses = format!(
+            "Bosses defeated: {}/{}",
+            model.bosses_killed,
+            model.config.bosses.len()
+        );
+        let stats = match model.options.daily {
+            Some(daily) => {
+                let recorded = history.daily_result(daily).unwrap_or(model.score);
+                format!(
+                    "{}\nScore: {}\nRooms cleared: {}\n{}\nRecorded: {}\nStreak: {}",
+                    daily.name(),
+                    model.score,
+                    model.rooms_cleared,
+                    bosses,
+                    recorded,
+                    history.daily_streak
+                )
+            }
+            None => self.category_results(model, &entry, best, bosses),
+        };
+        format!("{}\n{}\n\nTry Again\nCtrl + R", title, stats)
+    }
+
+    fn category_results(
+        &self,
+        model: &Model,
+        entry: &LeaderboardEntry,
+        best: Score,
+        bosses: String,
+    ) -> String {
+        match entry.category {
+            LeaderboardCategory::Normal => format!(
+                "Score: {}\nRooms cleared: {}\n{}\nBest: {}",
+                model.score, model.rooms_cleared, bosses, best
+            ),
+            LeaderboardCategory::Endless => format!(
+                "Score: {}\nRooms cleared: {}\nLoop: {}\nBest endless: {}",
+                model.score, model.rooms_cleared, entry.endless_loop, best
+            ),
+            LeaderboardCategory::TimeAttack => format!(
+                "Rooms cleared: {}\nScore: {}\nBest time attack: {}",
+                model.rooms_cleared, model.score, best
+            ),
+            LeaderboardCategory::BossRush => {
+                let seconds = model.game_time.as_f32() as u64;
+                format!(
+                    "{}\nTime: {}:{:02}\nScore: {}\nBest boss rush: {}",
+                    bosses,
+                    seconds / 60,
+                    seconds % 60,
+                    model.score,
+                    best
+                )
+            }
+            LeaderboardCategory::Pacifist => format!(
+                "Rooms cleared: {}\nScore: {}\nBest pacifist: {}",
+                model.rooms_cleared, model.score, best
+            ),
+        }
+    }
+
+    fn status_color(&self, kind: StatusKind) -> Rgba<f32> {
+        let palette = &self.assets.palette;
+        match kind {
+            StatusKind::Burn => palette.burn,
+            StatusKind::Slow => palette.slow,
+            StatusKind::Stun => palette.stun,
+            StatusKind::Poison => palette.poison,
+        }
+    }
+
     pub fn draw_texture(
         &self,
         quad: Aabb2<f32>,
@@ -415,22 +824,24 @@ impl GameRender {
         );
     }
 
-    pub fn draw_ui(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+    pub fn draw_ui(
+        &self,
+        model: &Model,
+        leaderboard: &Leaderboard,
+        history: &RunHistory,
+        show_minimap: bool,
+        show_choices: bool,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
         let frame_view = framebuffer.size().as_f32();
         let game_view = Aabb2::point(model.camera.center).extend_symmetric(
             vec2(framebuffer.size().as_f32().aspect(), 1.0) * model.camera.fov / 2.0,
         );
 
-        if model.player.health.is_min() {
-            // Death screen
-
+        if model.is_game_over() {
+            // Results screen
             let pos = game_view.center() + vec2(0.0, 0.05) * game_view.size();
-            let text = format!(
-                "You Died\nScore: {}\nRooms cleared: {}\nBosses defeated: {}/2\n\nTry Again\nCtrl + R",
-                model.score,
-                model.rooms_cleared,
-                model.bosses_killed
-            );
+            let text = self.results_text(model, leaderboard, history);
             self.assets.font.draw(
                 framebuffer,
                 &model.camera,
@@ -464,6 +875,124 @@ impl GameRender {
             );
         }
 
+        if let GameMode::TimeAttack = model.options.mode {
+            let seconds = model.time_left.as_f32().ceil() as u64;
+            self.assets.font.draw(
+                framebuffer,
+                &model.camera,
+                &format!("TIME: {}:{:02}", seconds / 60, seconds % 60),
+                vec2::splat(geng::TextAlign::CENTER),
+                mat3::translate(pos - vec2(0.0, 3.0)) * mat3::scale_uniform(1.2),
+                self.assets.palette.text,
+            );
+        }
+
+        if model.in_starting_room() {
+            // Mode selection
+            let pos = game_view.center() - vec2(0.0, 0.8) * game_view.size() / 2.0;
+            let text = match model.options.daily {
+                Some(daily) => std::iter::once(daily.name())
+                    .chain(model.options.mutators.iter().map(Mutator::name))
+                    .join("\n"),
+                None => {
+                    let controls = &self.assets.controls;
+                    let mutators = model.config.mutators.iter().enumerate().map(|(i, config)| {
+                        let marker = if model.options.mutators.contains(&config.mutator) {
+                            "[X]"
+                        } else {
+                            "[ ]"
+                        };
+                        let keys = controls.mutators.get(i).map_or(&[][..], Vec::as_slice);
+                        format!(
+                            "{} {} {} x{:.1}",
+                            key_name(keys),
+                            marker,
+                            config.mutator.name(),
+                            config.score_multiplier
+                        )
+                    });
+                    std::iter::once(format!(
+                        "MODE: {}\n{} to change, {} for daily\n{}: start with {:?}, {} for unlocks",
+                        model.options.mode.name(),
+                        controls.modes.iter().map(|keys| key_name(keys)).join("/"),
+                        key_name(&controls.daily),
+                        key_name(&controls.starting_weapon),
+                        model.options.starting_weapon,
+                        key_name(&controls.unlocks),
+                    ))
+                    .chain(mutators)
+                    .join("\n")
+                }
+            };
+            self.assets.font.draw(
+                framebuffer,
+                &model.camera,
+                &text,
+                vec2(geng::TextAlign::CENTER, geng::TextAlign::BOTTOM),
+                mat3::translate(pos),
+                self.assets.palette.text,
+            );
+        } else if !model.options.mutators.is_empty() {
+            // Active mutators
+            let scale = pixel_scale(framebuffer);
+            let pos = vec2(0.05, 0.05) * frame_view;
+            let text = model.options.mutators.iter().map(Mutator::name).join("\n");
+            self.assets.font.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                &text,
+                vec2(geng::TextAlign::LEFT, geng::TextAlign::BOTTOM),
+                mat3::translate(pos) * mat3::scale_uniform(scale * 8.0),
+                self.assets.palette.text,
+            );
+        }
+
+        // Endless loop
+        let endless_loop = model.endless_loop();
+        if endless_loop > 0 {
+            let scale = pixel_scale(framebuffer);
+            let pos = vec2(0.05, 0.95) * frame_view;
+            let text = std::iter::once(format!("LOOP {}", endless_loop))
+                .chain(model.endless_modifiers.iter().map(EndlessModifier::name))
+                .join("\n");
+            self.assets.font.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                &text,
+                vec2(geng::TextAlign::LEFT, geng::TextAlign::TOP),
+                mat3::translate(pos) * mat3::scale_uniform(scale * 8.0),
+                self.assets.palette.text,
+            );
+        }
+
+        if let Some(encounter) = &model.boss_encounter {
+            self.draw_boss_encounter(model, encounter, framebuffer);
+        }
+
+        if show_minimap {
+            self.draw_minimap(model, framebuffer);
+        }
+
+        self.draw_upgrade_choices(model, show_choices, framebuffer);
+        if show_choices {
+            self.draw_held_upgrades(model, framebuffer);
+        }
+
+        if let Some(challenge) = &model.challenge {
+            let scale = pixel_scale(framebuffer);
+            self.assets.font.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                &format!(
+                    "SURVIVE: {}",
+                    challenge.timer.value().as_f32().ceil() as u64
+                ),
+                vec2(geng::TextAlign::CENTER, geng::TextAlign::BOTTOM),
+                mat3::translate(vec2(0.5, 0.07) * frame_view) * mat3::scale_uniform(scale * 8.0),
+                self.assets.palette.text,
+            );
+        }
+
         // Difficulty icon
         let pos = vec2(0.95, 0.95) * frame_view;
         let steps = [15.0, 35.0];
@@ -516,6 +1045,674 @@ impl GameRender {
         }
     }
 
+    /// Shop of the permanent unlocks in the starting room.
+    pub fn draw_unlocks(
+        &self,
+        progress: &MetaProgress,
+        config: &MetaConfig,
+        confirm_reset: bool,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let frame_view = framebuffer.size().as_f32();
+        let scale = pixel_scale(framebuffer);
+        let camera = &geng::PixelPerfectCamera;
+        let palette = &self.assets.palette;
+
+        let mut background = palette.background;
+        background.a = 0.9;
+        self.geng.draw2d().quad(
+            framebuffer,
+            camera,
+            Aabb2::ZERO.extend_positive(frame_view),
+            background,
+        );
+
+        let line = scale * 12.0;
+        let mut pos = vec2(0.5, 0.85) * frame_view;
+        self.assets.font.draw(
+            framebuffer,
+            camera,
+            &format!("UNLOCKS - COINS: {}", progress.coins),
+            vec2::splat(geng::TextAlign::CENTER),
+            mat3::translate(pos) * mat3::scale_uniform(scale * 10.0),
+            palette.text,
+        );
+        pos.y -= line * 2.0;
+        for (i, unlock) in config.unlocks.iter().enumerate() {
+            let (status, color) = if progress.is_unlocked(&unlock.unlock) {
+                ("[X]".to_string(), palette.upgrade_rare)
+            } else if progress.coins >= unlock.cost {
+                (format!("{} coins", unlock.cost), palette.text)
+            } else {
+                (format!("{} coins", unlock.cost), palette.wall_block)
+            };
+            self.assets.font.draw(
+                framebuffer,
+                camera,
+                &format!("{} {} - {}", i + 1, unlock.unlock.name(), status),
+                vec2::splat(geng::TextAlign::CENTER),
+                mat3::translate(pos) * mat3::scale_uniform(scale * 7.0),
+                color,
+            );
+            pos.y -= line;
+        }
+        pos.y -= line;
+        let reset = key_name(&self.assets.controls.reset_progress);
+        let (hint, color) = if confirm_reset {
+            (
+                format!("Press {reset} again to reset all coins and unlocks"),
+                palette.damage,
+            )
+        } else {
+            (
+                format!("1-9 to buy, {reset} to reset progress"),
+                palette.text,
+            )
+        };
+        self.assets.font.draw(
+            framebuffer,
+            camera,
+            &hint,
+            vec2::splat(geng::TextAlign::CENTER),
+            mat3::translate(pos) * mat3::scale_uniform(scale * 6.0),
+            color,
+        );
+    }
+
+    /// Collected upgrades and the resulting stats compared to the base ones.
+    pub fn draw_pause(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let frame_view = framebuffer.size().as_f32();
+        let scale = pixel_scale(framebuffer);
+        let camera = &geng::PixelPerfectCamera;
+        let palette = &self.assets.palette;
+
+        let mut background = palette.background;
+        background.a = 0.85;
+        self.geng.draw2d().quad(
+            framebuffer,
+            camera,
+            Aabb2::ZERO.extend_positive(frame_view),
+            background,
+        );
+        self.assets.font.draw(
+            framebuffer,
+            camera,
+            "PAUSED",
+            vec2::splat(geng::TextAlign::CENTER),
+            mat3::translate(vec2(0.5, 0.9) * frame_view) * mat3::scale_uniform(scale * 12.0),
+            palette.text,
+        );
+
+        let line = scale * 12.0;
+        let text = |framebuffer: &mut ugli::Framebuffer, text: &str, pos: vec2<f32>, color| {
+            self.assets.font.draw(
+                framebuffer,
+                camera,
+                text,
+                vec2(geng::TextAlign::LEFT, geng::TextAlign::CENTER),
+                mat3::translate(pos) * mat3::scale_uniform(scale * 6.0),
+                color,
+            );
+        };
+
+        // Upgrades
+        let mut pos = vec2(0.1, 0.78) * frame_view;
+        text(framebuffer, "UPGRADES", pos, palette.text);
+        if model.player.upgrades.is_empty() {
+            pos.y -= line;
+            text(framebuffer, "NONE YET", pos, palette.wall_block);
+        }
+        let columns = 2;
+        let column_width = frame_view.x * 0.18;
+        for (i, (effect, name, color)) in self.held_upgrade_rows(model, 20).into_iter().enumerate()
+        {
+            let pos = pos
+                + vec2(
+                    (i % columns) as f32 * column_width,
+                    -((i / columns + 1) as f32 * line * 1.5),
+                );
+            if let Some(effect) = effect {
+                self.draw_texture(
+                    Aabb2::point(pos + vec2(scale * 8.0, 0.0)),
+                    self.upgrade_texture(effect),
+                    color,
+                    framebuffer,
+                );
+            }
+            text(framebuffer, &name, pos + vec2(scale * 20.0, 0.0), color);
+        }
+
+        // Stats
+        let stats = &model.player.stats;
+        let base = &model.config.player;
+        let mut lines = vec![
+            ("SPEED".to_string(), stats.speed, base.speed),
+            (
+                "ACCELERATION".to_string(),
+                stats.acceleration,
+                base.acceleration,
+            ),
+        ];
+        for (name, weapon) in [
+            ("WHIP", Weapon::Whip),
+            ("DASH", Weapon::Dash),
+            ("BOW", Weapon::Bow),
+            ("ROD", Weapon::FishingRod),
+        ] {
+            let current = stats.weapon(weapon);
+            let base = base.weapon(weapon);
+            lines.extend([
+                (format!("{name} DAMAGE"), current.damage, base.damage),
+                (format!("{name} WIDTH"), current.width, base.width),
+                (
+                    format!("{name} RANGE"),
+                    current.max_distance,
+                    base.max_distance,
+                ),
+                (
+                    format!("{name} COOLDOWN"),
+                    current.cooldown.max(),
+                    base.cooldown.max(),
+                ),
+            ]);
+        }
+
+        let mut pos = vec2(0.55, 0.78) * frame_view;
+        text(framebuffer, "STATS", pos, palette.text);
+        for (name, value, base) in lines {
+            pos.y -= line;
+            text(framebuffer, &name, pos, palette.text);
+            let delta = value - base;
+            let (value, color) = if delta == R32::ZERO {
+                (format!("{:.1}", value.as_f32()), palette.text)
+            } else {
+                (
+                    format!("{:.1} ({:+.1})", value.as_f32(), delta.as_f32()),
+                    palette.upgrade_rare,
+                )
+            };
+            text(
+                framebuffer,
+                &value,
+                pos + vec2(frame_view.x * 0.2, 0.0),
+                color,
+            );
+        }
+    }
+
+    fn draw_boss_encounter(
+        &self,
+        model: &Model,
+        encounter: &BossEncounter,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let frame_view = framebuffer.size().as_f32();
+        let scale = pixel_scale(framebuffer);
+
+        // Name card
+        if encounter.in_intro() {
+            let t = crate::util::smoothstep(encounter.intro.get_ratio()).as_f32();
+            let mut color = self.assets.palette.text;
+            color.a = (t * 3.0).min(1.0);
+            self.assets.font.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                &encounter.name,
+                vec2::splat(geng::TextAlign::CENTER),
+                mat3::translate(vec2(0.5, 0.65) * frame_view) * mat3::scale_uniform(scale * 24.0),
+                color,
+            );
+        }
+
+        let bosses: Vec<&Enemy> = model.enemies.iter().filter(|enemy| enemy.is_boss).collect();
+        if bosses.is_empty() {
+            return;
+        }
+        let (health, max_health) = bosses
+            .iter()
+            .fold((Hp::ZERO, Hp::ZERO), |(acc, max), boss| {
+                (acc + boss.health.value(), max + boss.health.max())
+            });
+        let ratio = (health / max_health).as_f32();
+
+        // Health bar
+        let bar = Aabb2::point(vec2(0.5, 0.07) * frame_view)
+            .extend_symmetric(vec2(frame_view.x * 0.3, scale * 4.0));
+        self.geng.draw2d().quad(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            bar,
+            self.assets.palette.health,
+        );
+        let bar = bar.extend_uniform(-scale);
+        self.geng.draw2d().quad(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            bar,
+            self.assets.palette.background,
+        );
+        let fill = bar.extend_right((ratio - 1.0) * bar.width());
+        self.geng.draw2d().quad(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            fill,
+            self.assets.palette.health,
+        );
+
+        // Phase markers
+        if let [boss] = bosses[..] {
+            for phase in &boss.stats.phases {
+                let x = bar.min.x + bar.width() * phase.health_threshold.as_f32();
+                let marker = Aabb2::point(vec2(x, bar.center().y))
+                    .extend_symmetric(vec2(scale * 0.5, bar.height() / 2.0 + scale * 2.0));
+                self.geng.draw2d().quad(
+                    framebuffer,
+                    &geng::PixelPerfectCamera,
+                    marker,
+                    self.assets.palette.text,
+                );
+            }
+        }
+
+        self.assets.font.draw(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            &encounter.name,
+            vec2(geng::TextAlign::CENTER, geng::TextAlign::BOTTOM),
+            mat3::translate(vec2(bar.center().x, bar.max.y + scale * 3.0))
+                * mat3::scale_uniform(scale * 8.0),
+            self.assets.palette.text,
+        );
+    }
+
+    /// Upgrades collected this run and the synergies between them.
+    fn draw_held_upgrades(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let frame_view = framebuffer.size().as_f32();
+        let scale = pixel_scale(framebuffer);
+        let camera = &geng::PixelPerfectCamera;
+        let palette = &self.assets.palette;
+
+        let mut lines: Vec<(String, Rgba<f32>)> = vec![("UPGRADES".to_string(), palette.text)];
+        lines.extend(
+            self.held_upgrade_rows(model, 12)
+                .into_iter()
+                .map(|(_, text, color)| (text, color)),
+        );
+
+        lines.push((String::new(), palette.text));
+        lines.push(("SYNERGIES".to_string(), palette.text));
+        for synergy in Synergy::ALL {
+            if model.player.has_synergy(synergy) {
+                lines.push((
+                    format!("{}: {}", synergy.name(), synergy.description()),
+                    palette.upgrade_legendary,
+                ));
+                continue;
+            }
+            // Show the ones halfway there
+            let missing: Vec<_> = synergy
+                .requires()
+                .into_iter()
+                .filter(|required| !model.player.has_upgrade(required))
+                .collect();
+            if missing.len() < synergy.requires().len() {
+                let missing = missing.iter().map(UpgradeEffect::name).join(", ");
+                lines.push((
+                    format!("{}: NEEDS {}", synergy.name(), missing),
+                    palette.wall_block,
+                ));
+            }
+        }
+
+        let pos = vec2(0.05, 0.8) * frame_view;
+        for (i, (text, color)) in lines.into_iter().enumerate() {
+            self.assets.font.draw(
+                framebuffer,
+                camera,
+                &text,
+                vec2(geng::TextAlign::LEFT, geng::TextAlign::TOP),
+                mat3::translate(pos - vec2(0.0, i as f32 * scale * 8.0))
+                    * mat3::scale_uniform(scale * 6.0),
+                color,
+            );
+        }
+    }
+
+    /// Collected upgrades with the duplicates grouped into one row.
+    /// Rows past `max_rows` are summed up in the last one, which has no upgrade.
+    fn held_upgrade_rows<'a>(
+        &self,
+        model: &'a Model,
+        max_rows: usize,
+    ) -> Vec<(Option<&'a UpgradeEffect>, String, Rgba<f32>)> {
+        let mut held: Vec<(&UpgradeEffect, usize)> = Vec::new();
+        for effect in &model.player.upgrades {
+            match held.iter_mut().find(|(other, _)| *other == effect) {
+                Some((_, count)) => *count += 1,
+                None => held.push((effect, 1)),
+            }
+        }
+
+        let shown = if held.len() > max_rows {
+            max_rows.saturating_sub(1)
+        } else {
+            held.len()
+        };
+        let mut rows: Vec<_> = held[..shown]
+            .iter()
+            .map(|&(effect, count)| {
+                let text = if count > 1 {
+                    format!("{} x{}", effect.name(), count)
+                } else {
+                    effect.name()
+                };
+                (Some(effect), text, self.rarity_color(effect.rarity()))
+            })
+            .collect();
+        if shown < held.len() {
+            rows.push((
+                None,
+                format!("+{} MORE", held.len() - shown),
+                self.assets.palette.wall_block,
+            ));
+        }
+        rows
+    }
+
+    fn draw_upgrade_choices(
+        &self,
+        model: &Model,
+        show_choices: bool,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let frame_view = framebuffer.size().as_f32();
+        let scale = pixel_scale(framebuffer);
+        let camera = &geng::PixelPerfectCamera;
+
+        // Tooltip
+        if let Some(upgrade) = model
+            .hovered_upgrade()
+            .and_then(|index| model.upgrades.get(index))
+        {
+            if let Some(pos) = model.camera.world_to_screen(
+                frame_view,
+                upgrade.collider.position.as_f32() + vec2(0.0, 1.0),
+            ) {
+                let text = std::iter::once(upgrade.effect.name())
+                    .chain(model.upgrade_deltas(&upgrade.effect))
+                    .join("\n");
+                self.assets.font.draw(
+                    framebuffer,
+                    camera,
+                    &text,
+                    vec2(geng::TextAlign::CENTER, geng::TextAlign::BOTTOM),
+                    mat3::translate(pos) * mat3::scale_uniform(scale * 6.0),
+                    self.assets.palette.text,
+                );
+            }
+        }
+
+        let choices = model.upgrade_choices();
+        if choices.is_empty() {
+            return;
+        }
+
+        // Rerolls and banishes
+        self.assets.font.draw(
+            framebuffer,
+            camera,
+            &format!("REROLL x{}  BANISH x{}", model.rerolls, model.banishes),
+            vec2(geng::TextAlign::CENTER, geng::TextAlign::BOTTOM),
+            mat3::translate(vec2(0.5, 0.15) * frame_view) * mat3::scale_uniform(scale * 6.0),
+            self.assets.palette.text,
+        );
+
+        if !show_choices {
+            return;
+        }
+
+        // Choice panel
+        let panel = Aabb2::point(vec2(0.5, 0.55) * frame_view)
+            .extend_symmetric(vec2(frame_view.x * 0.35, frame_view.y * 0.2));
+        let mut background = self.assets.palette.background;
+        background.a = 0.9;
+        self.geng
+            .draw2d()
+            .quad(framebuffer, camera, panel, background);
+        let column = panel.width() / choices.len() as f32;
+        for (i, &index) in choices.iter().enumerate() {
+            let Some(upgrade) = model.upgrades.get(index) else {
+                continue;
+            };
+            let x = panel.min.x + column * (i as f32 + 0.5);
+            self.draw_texture(
+                Aabb2::point(vec2(x, panel.max.y - scale * 16.0)),
+                self.upgrade_texture(&upgrade.effect),
+                self.rarity_color(upgrade.effect.rarity()),
+                framebuffer,
+            );
+            let text = std::iter::once(format!("{}: {}", i + 1, upgrade.effect.name()))
+                .chain(model.upgrade_deltas(&upgrade.effect))
+                .join("\n");
+            self.assets.font.draw(
+                framebuffer,
+                camera,
+                &text,
+                vec2(geng::TextAlign::CENTER, geng::TextAlign::TOP),
+                mat3::translate(vec2(x, panel.max.y - scale * 32.0))
+                    * mat3::scale_uniform(scale * 6.0),
+                self.assets.palette.text,
+            );
+        }
+    }
+
+    fn draw_minimap(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let frame_view = framebuffer.size().as_f32();
+        let scale = pixel_scale(framebuffer);
+        let camera = &geng::PixelPerfectCamera;
+
+        let frame = Aabb2::point(vec2(0.95, 0.05) * frame_view)
+            .extend_left(scale * 64.0)
+            .extend_up(scale * 40.0);
+        let mut background = self.assets.palette.background;
+        background.a = 0.8;
+        self.geng
+            .draw2d()
+            .quad(framebuffer, camera, frame, background);
+
+        let Some(bounds) = Aabb2::points_bounding_box(
+            model.rooms.iter().flat_map(|(_, room)| room.area.corners()),
+        ) else {
+            return;
+        };
+        let bounds = bounds.map(Coord::as_f32);
+        let view = frame.extend_uniform(-scale * 3.0);
+        let zoom = (view.width() / bounds.width()).min(view.height() / bounds.height());
+        let to_map = |pos: vec2<f32>| view.center() + (pos - bounds.center()) * zoom;
+        let map_aabb = |aabb: Aabb2<Coord>| {
+            let aabb = aabb.map(Coord::as_f32);
+            Aabb2::from_corners(to_map(aabb.min), to_map(aabb.max))
+        };
+
+        let player = model.player.body.collider.position;
+        let t = (model.real_time.as_f32() * 8.0 * f32::PI).sin() * 0.5 + 0.5;
+        for (room_idx, room) in &model.rooms {
+            // Floor
+            let color = if room.contains(player) {
+                self.assets.palette.upgrade
+            } else if room.kind == RoomKind::Boss {
+                self.assets.palette.elite
+            } else {
+                self.floor_color(room.kind)
+            };
+            // Compression
+            let mut squash = self.assets.palette.damage;
+            squash.a = if model.squash_warnings.contains(&room_idx) {
+                t
+            } else if model.closing_walls.iter().any(|&(idx, _)| idx == room_idx) {
+                0.4
+            } else {
+                0.0
+            };
+            for &part in room.parts() {
+                let part = map_aabb(part);
+                self.geng.draw2d().quad(framebuffer, camera, part, color);
+                self.geng.draw2d().quad(framebuffer, camera, part, squash);
+            }
+
+            // Link to the previous room
+            if let Some(prev) = room
+                .unlocked_after
+                .and_then(|(prev_idx, _)| model.rooms.get(prev_idx))
+            {
+                let a = to_map(prev.area.center().as_f32());
+                let b = to_map(room.area.center().as_f32());
+                self.geng.draw2d().draw2d(
+                    framebuffer,
+                    camera,
+                    &draw2d::Segment::new(Segment(a, b), scale * 0.5, self.assets.palette.text),
+                );
+            }
+        }
+
+        // Walls
+        for (room_idx, dir, collider) in &model.room_colliders {
+            let color = if model.is_breakable_wall(*room_idx, *dir) {
+                self.assets.palette.wall
+            } else {
+                self.assets.palette.wall_block
+            };
+            let wall = map_aabb(collider.compute_aabb()).extend_uniform(scale * 0.5);
+            self.geng.draw2d().quad(framebuffer, camera, wall, color);
+        }
+
+        // Player
+        let pos = to_map(player.as_f32());
+        self.geng.draw2d().quad(
+            framebuffer,
+            camera,
+            Aabb2::point(pos).extend_uniform(scale),
+            self.assets.palette.player,
+        );
+
+        // Upcoming boss
+        if let Some(rooms) = model.rooms_until_boss() {
+            let text = if rooms == 0 {
+                "NEXT: BOSS".to_string()
+            } else {
+                format!("BOSS IN {}", rooms + 1)
+            };
+            self.assets.font.draw(
+                framebuffer,
+                camera,
+                &text,
+                vec2(geng::TextAlign::RIGHT, geng::TextAlign::BOTTOM),
+                mat3::translate(vec2(frame.max.x, frame.max.y + scale * 2.0))
+                    * mat3::scale_uniform(scale * 6.0),
+                self.assets.palette.text,
+            );
+        }
+    }
+
+    fn rarity_color(&self, rarity: Rarity) -> Rgba<f32> {
+        match rarity {
+            Rarity::Common => self.assets.palette.upgrade,
+            Rarity::Rare => self.assets.palette.upgrade_rare,
+            Rarity::Legendary => self.assets.palette.upgrade_legendary,
+        }
+    }
+
+    fn upgrade_texture(&self, effect: &UpgradeEffect) -> &ugli::Texture {
+        match effect {
+            UpgradeEffect::Width => &self.assets.sprites.width,
+            UpgradeEffect::Range => &self.assets.sprites.range,
+            UpgradeEffect::Damage => &self.assets.sprites.damage,
+            UpgradeEffect::Speed => &self.assets.sprites.speed,
+            UpgradeEffect::Difficulty => &self.assets.sprites.skull,
+            UpgradeEffect::Ignite => &self.assets.sprites.ignite,
+            UpgradeEffect::Venom => &self.assets.sprites.venom,
+            UpgradeEffect::Pierce => &self.assets.sprites.bow,
+            UpgradeEffect::Trail => &self.assets.sprites.dash,
+            UpgradeEffect::Echo => &self.assets.sprites.whip,
+            UpgradeEffect::Health => &self.assets.sprites.heal,
+            UpgradeEffect::Cooldown => &self.assets.sprites.speed,
+            UpgradeEffect::Weapon(weapon) => match weapon {
+                Weapon::Whip => &self.assets.sprites.whip,
+                Weapon::Dash => &self.assets.sprites.dash,
+                Weapon::Bow => &self.assets.sprites.bow,
+                Weapon::FishingRod => &self.assets.sprites.fishing_rod,
+            },
+        }
+    }
+
+    /// Outline of the room behind the side, with what it holds.
+    fn draw_room_preview(
+        &self,
+        model: &Model,
+        room: &Room,
+        dir: Direction,
+        preview: &RoomPreview,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let area = room.neighbour_area(dir, preview.size);
+        let mut color = self.assets.palette.text;
+        color.a = 0.5;
+        let width = 0.15;
+        for part in preview.shape.parts_in(area) {
+            let part = part.map(Coord::as_f32);
+            for edge in [
+                part.extend_left(width - part.width()),
+                part.extend_right(width - part.width()),
+                part.extend_down(width - part.height()),
+                part.extend_up(width - part.height()),
+            ] {
+                self.geng
+                    .draw2d()
+                    .quad(framebuffer, &model.camera, edge, color);
+            }
+        }
+
+        let center = area.center().as_f32();
+        let mut text = preview.kind.name().to_string();
+        if let RoomKind::Combat | RoomKind::Challenge = preview.kind {
+            text += &format!("\nx{:.1}", preview.difficulty);
+        }
+        self.assets.font.draw(
+            framebuffer,
+            &model.camera,
+            &text,
+            vec2(geng::TextAlign::CENTER, geng::TextAlign::TOP),
+            mat3::translate(center),
+            self.assets.palette.text,
+        );
+
+        if let Some(reward) = &preview.reward {
+            if let Some(pos) = model
+                .camera
+                .world_to_screen(framebuffer.size().as_f32(), center + vec2(0.0, 1.5))
+            {
+                self.draw_texture(
+                    Aabb2::point(pos),
+                    self.upgrade_texture(reward),
+                    self.rarity_color(reward.rarity()),
+                    framebuffer,
+                );
+            }
+        }
+    }
+
+    /// Floor color of the room, masked by the background shader to draw its pattern.
+    fn floor_color(&self, kind: RoomKind) -> Rgba<f32> {
+        let palette = &self.assets.palette;
+        match kind {
+            RoomKind::Combat | RoomKind::Boss => palette.room,
+            RoomKind::Treasure => palette.room_treasure,
+            RoomKind::Shop => palette.room_shop,
+            RoomKind::Challenge => palette.room_challenge,
+            RoomKind::Rest => palette.room_rest,
+        }
+    }
+
     pub fn draw_collider(
         &self,
         collider: &Collider,
@@ -675,6 +1872,17 @@ impl GameRender {
     }
 }
 
+/// Name of the first key bound to an action, for the hints.
+fn key_name(keys: &[geng_utils::key::EventKey]) -> String {
+    match keys.first() {
+        Some(geng_utils::key::EventKey::Key(key)) => {
+            format!("{key:?}").trim_start_matches("Digit").to_string()
+        }
+        Some(geng_utils::key::EventKey::Mouse(button)) => format!("{button:?} click"),
+        None => "unbound".to_string(),
+    }
+}
+
 fn pixel_scale(framebuffer: &ugli::Framebuffer) -> f32 {
     framebuffer.size().y as f32 / crate::GAME_RESOLUTION.y as f32
 }